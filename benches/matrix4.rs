@@ -0,0 +1,155 @@
+// Compares the `f32` SIMD specializations of `Matrix4` against the generic scalar code,
+// which `Plain` always goes through since it does not override the `Scalar` hooks.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use linmath::*;
+use num_traits::{One, Zero};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+struct Plain(f32);
+
+impl Add for Plain {
+    type Output = Plain;
+
+    fn add(self, rhs: Plain) -> Plain {
+        Plain(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Plain {
+    type Output = Plain;
+
+    fn sub(self, rhs: Plain) -> Plain {
+        Plain(self.0 - rhs.0)
+    }
+}
+
+impl Mul for Plain {
+    type Output = Plain;
+
+    fn mul(self, rhs: Plain) -> Plain {
+        Plain(self.0 * rhs.0)
+    }
+}
+
+impl Div for Plain {
+    type Output = Plain;
+
+    fn div(self, rhs: Plain) -> Plain {
+        Plain(self.0 / rhs.0)
+    }
+}
+
+impl Neg for Plain {
+    type Output = Plain;
+
+    fn neg(self) -> Plain {
+        Plain(-self.0)
+    }
+}
+
+impl Zero for Plain {
+    fn zero() -> Plain {
+        Plain(0.0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0.0
+    }
+}
+
+impl One for Plain {
+    fn one() -> Plain {
+        Plain(1.0)
+    }
+}
+
+impl Scalar for Plain {}
+
+impl SignedScalar for Plain {}
+
+fn matrix<T: Scalar>(f: impl Fn(f32) -> T) -> Matrix4<T> {
+    let v = |i: f32| f((i * 12.9898).sin() * 4.0);
+    Matrix4::new(
+        Vector4::new(v(0.0), v(1.0), v(2.0), v(3.0)),
+        Vector4::new(v(4.0), v(5.0), v(6.0), v(7.0)),
+        Vector4::new(v(8.0), v(9.0), v(10.0), v(11.0)),
+        Vector4::new(v(12.0), v(13.0), v(14.0), v(15.0)),
+    )
+}
+
+fn bench_matrix4(c: &mut Criterion) {
+    let a = matrix(|v| v);
+    let b = Matrix4::transpose(&a);
+    let pa = matrix(Plain);
+    let pb = Matrix4::transpose(&pa);
+
+    let mut group = c.benchmark_group("matrix4_mul_matrix");
+    group.bench_function("simd", |bench| bench.iter(|| black_box(a) * black_box(b)));
+    group.bench_function("scalar", |bench| {
+        bench.iter(|| black_box(pa) * black_box(pb))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("matrix4_mul_vector");
+    group.bench_function("simd", |bench| bench.iter(|| black_box(a) * black_box(b.x)));
+    group.bench_function("scalar", |bench| {
+        bench.iter(|| black_box(pa) * black_box(pb.x))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("matrix4_transpose");
+    group.bench_function("simd", |bench| {
+        bench.iter(|| Matrix4::transpose(black_box(&a)))
+    });
+    group.bench_function("scalar", |bench| {
+        bench.iter(|| Matrix4::transpose(black_box(&pa)))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("matrix4_inverse");
+    group.bench_function("simd", |bench| {
+        bench.iter(|| Matrix4::inverse(black_box(&a)))
+    });
+    group.bench_function("scalar", |bench| {
+        bench.iter(|| Matrix4::inverse(black_box(&pa)))
+    });
+    group.finish();
+
+    let points: Vec<Vector3<f32>> = (0..1024)
+        .map(|i| {
+            let i = i as f32;
+            Vector3::new(i, -i, 0.5 * i)
+        })
+        .collect();
+    let mut out = vec![Vector3::splat(0.0); points.len()];
+
+    let mut group = c.benchmark_group("matrix4_transform_points");
+    group.bench_function("vector3", |bench| {
+        bench.iter(|| {
+            for (o, p) in out.iter_mut().zip(&points) {
+                *o = Matrix4::transform_point(black_box(&a), p);
+            }
+        })
+    });
+    group.bench_function("slice", |bench| {
+        bench.iter(|| {
+            out.copy_from_slice(&points);
+            Matrix4::transform_points_in_place(black_box(&a), &mut out);
+        })
+    });
+    let wide: Vec<Vector3x8<f32>> = points.chunks_exact(8).map(Vector3x8::from_slice).collect();
+    let mut wide_out = wide.clone();
+    group.bench_function("vector3x8", |bench| {
+        bench.iter(|| {
+            for (o, p) in wide_out.iter_mut().zip(&wide) {
+                *o = black_box(a) * *p;
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_matrix4);
+criterion_main!(benches);
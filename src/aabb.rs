@@ -0,0 +1,225 @@
+use crate::matrix::*;
+use crate::scalar::Scalar;
+use crate::vector::*;
+
+macro_rules! generate_aabb_n {
+    ($AabbN: ident, $VectorN: ident, $($field: ident),+) => {
+        /// Axis-aligned box spanning `min..=max` on every axis.
+        #[repr(C)]
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub struct $AabbN<T> {
+            pub min: $VectorN<T>,
+            pub max: $VectorN<T>,
+        }
+
+        impl<T> $AabbN<T> {
+            #[inline]
+            pub fn new(min: $VectorN<T>, max: $VectorN<T>) -> $AabbN<T> {
+                $AabbN { min, max }
+            }
+        }
+
+        impl<T: Scalar> $AabbN<T> {
+            /// Smallest box containing all `points`, `None` if there are none.
+            pub fn from_points(points: &[$VectorN<T>]) -> Option<$AabbN<T>> {
+                let (first, rest) = points.split_first()?;
+                let b = $AabbN::new(*first, *first);
+                Some(rest.iter().fold(b, |b, p| $AabbN::extend(&b, p)))
+            }
+
+            /// Smallest box containing `b` and `p`.
+            #[inline]
+            pub fn extend(b: &$AabbN<T>, p: &$VectorN<T>) -> $AabbN<T> {
+                $AabbN::new($VectorN::min(&b.min, p), $VectorN::max(&b.max, p))
+            }
+
+            /// Smallest box containing `a` and `b`.
+            #[inline]
+            pub fn union(a: &$AabbN<T>, b: &$AabbN<T>) -> $AabbN<T> {
+                $AabbN::new($VectorN::min(&a.min, &b.min), $VectorN::max(&a.max, &b.max))
+            }
+
+            /// Overlap of `a` and `b`, `None` if they are disjoint. Boxes touching on a face
+            /// intersect in a flat box.
+            #[inline]
+            pub fn intersection(a: &$AabbN<T>, b: &$AabbN<T>) -> Option<$AabbN<T>> {
+                let r = $AabbN::new($VectorN::max(&a.min, &b.min), $VectorN::min(&a.max, &b.max));
+                if $(r.min.$field <= r.max.$field)&&+ {
+                    Some(r)
+                } else {
+                    None
+                }
+            }
+
+            #[inline]
+            pub fn intersects(a: &$AabbN<T>, b: &$AabbN<T>) -> bool {
+                $(a.min.$field <= b.max.$field && b.min.$field <= a.max.$field)&&+
+            }
+
+            #[inline]
+            pub fn contains(b: &$AabbN<T>, p: &$VectorN<T>) -> bool {
+                $(b.min.$field <= p.$field && p.$field <= b.max.$field)&&+
+            }
+
+            /// Whether `inner` lies entirely inside `outer`.
+            #[inline]
+            pub fn contains_aabb(outer: &$AabbN<T>, inner: &$AabbN<T>) -> bool {
+                $AabbN::contains(outer, &inner.min) && $AabbN::contains(outer, &inner.max)
+            }
+
+            #[inline]
+            pub fn center(b: &$AabbN<T>) -> $VectorN<T> {
+                let two = T::one() + T::one();
+                $VectorN::new($((b.min.$field + b.max.$field) / two),+)
+            }
+
+            /// Size along each axis, `max - min`.
+            #[inline]
+            pub fn extents(b: &$AabbN<T>) -> $VectorN<T> {
+                b.max - b.min
+            }
+
+            /// The point of `b` closest to `p`, `p` itself if it is inside.
+            #[inline]
+            pub fn closest_point(b: &$AabbN<T>, p: &$VectorN<T>) -> $VectorN<T> {
+                $VectorN::min(&$VectorN::max(p, &b.min), &b.max)
+            }
+        }
+    };
+}
+
+generate_aabb_n!(Aabb2, Vector2, x, y);
+generate_aabb_n!(Aabb3, Vector3, x, y, z);
+
+impl<T: Scalar> Aabb2<T> {
+    #[inline]
+    pub fn area(b: &Aabb2<T>) -> T {
+        let e = Aabb2::extents(b);
+        e.x * e.y
+    }
+
+    /// Box enclosing `b` transformed by the affine `m`.
+    pub fn transform(m: &Matrix3<T>, b: &Aabb2<T>) -> Aabb2<T> {
+        // Arvo's method: each column contributes its smaller and larger product to the
+        // bounds of every output axis.
+        let mut r = Aabb2::new(Vector2::new(m.z.x, m.z.y), Vector2::new(m.z.x, m.z.y));
+        for (c, lo, hi) in [(&m.x, b.min.x, b.max.x), (&m.y, b.min.y, b.max.y)] {
+            let c = Vector2::new(c.x, c.y);
+            let (a, b) = (c * lo, c * hi);
+            r.min += Vector2::min(&a, &b);
+            r.max += Vector2::max(&a, &b);
+        }
+        r
+    }
+}
+
+impl<T: Scalar> Aabb3<T> {
+    #[inline]
+    pub fn surface_area(b: &Aabb3<T>) -> T {
+        let e = Aabb3::extents(b);
+        let two = T::one() + T::one();
+        two * (e.x * e.y + e.y * e.z + e.z * e.x)
+    }
+
+    #[inline]
+    pub fn volume(b: &Aabb3<T>) -> T {
+        let e = Aabb3::extents(b);
+        e.x * e.y * e.z
+    }
+
+    /// Box enclosing `b` transformed by the affine `m`.
+    pub fn transform(m: &Matrix4<T>, b: &Aabb3<T>) -> Aabb3<T> {
+        // Arvo's method, see `Aabb2::transform`.
+        let t = Vector3::new(m.w.x, m.w.y, m.w.z);
+        let mut r = Aabb3::new(t, t);
+        for (c, lo, hi) in [
+            (&m.x, b.min.x, b.max.x),
+            (&m.y, b.min.y, b.max.y),
+            (&m.z, b.min.z, b.max.z),
+        ] {
+            let c = Vector3::new(c.x, c.y, c.z);
+            let (a, b) = (c * lo, c * hi);
+            r.min += Vector3::min(&a, &b);
+            r.max += Vector3::max(&a, &b);
+        }
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_4;
+
+    #[test]
+    fn aabb_from_points() {
+        let b = Aabb3::from_points(&[
+            Vector3::new(1, 5, -2),
+            Vector3::new(-3, 2, 0),
+            Vector3::new(0, 7, 4),
+        ])
+        .unwrap();
+        assert_eq!(
+            b,
+            Aabb3::new(Vector3::new(-3, 2, -2), Vector3::new(1, 7, 4))
+        );
+        assert_eq!(Aabb3::<i32>::from_points(&[]), None);
+        assert_eq!(Aabb3::center(&b), Vector3::new(-1, 4, 1));
+        assert_eq!(Aabb3::extents(&b), Vector3::new(4, 5, 6));
+        assert_eq!(Aabb3::volume(&b), 120);
+        assert_eq!(Aabb3::surface_area(&b), 2 * (20 + 30 + 24));
+    }
+
+    #[test]
+    fn aabb_set_operations() {
+        let a = Aabb2::new(Vector2::new(0.0, 0.0), Vector2::new(2.0, 2.0));
+        let b = Aabb2::new(Vector2::new(1.0, -1.0), Vector2::new(3.0, 1.0));
+        let c = Aabb2::new(Vector2::new(2.5, 2.5), Vector2::new(3.0, 3.0));
+        assert_eq!(
+            Aabb2::union(&a, &b),
+            Aabb2::new(Vector2::new(0.0, -1.0), Vector2::new(3.0, 2.0))
+        );
+        assert_eq!(
+            Aabb2::intersection(&a, &b),
+            Some(Aabb2::new(Vector2::new(1.0, 0.0), Vector2::new(2.0, 1.0)))
+        );
+        assert!(Aabb2::intersects(&a, &b));
+        assert_eq!(Aabb2::intersection(&a, &c), None);
+        assert!(!Aabb2::intersects(&a, &c));
+        assert!(Aabb2::contains(&a, &Vector2::new(2.0, 0.5)));
+        assert!(!Aabb2::contains(&a, &Vector2::new(2.1, 0.5)));
+        assert!(Aabb2::contains_aabb(&Aabb2::union(&a, &c), &c));
+        assert_eq!(
+            Aabb2::closest_point(&a, &Vector2::new(-1.0, 1.5)),
+            Vector2::new(0.0, 1.5)
+        );
+        assert_eq!(Aabb2::area(&a), 4.0);
+    }
+
+    #[test]
+    fn aabb_transform() {
+        let b = Aabb3::new(Vector3::new(-1.0, -1.0, -1.0), Vector3::new(1.0, 1.0, 1.0));
+        let m = Matrix4::translate(&Vector3::new(10.0, 0.0, 0.0))
+            * Matrix4::rotate(FRAC_PI_4, &Vector3::new(0.0, 0.0, 1.0));
+        let r = Aabb3::transform(&m, &b);
+        let s = 2.0f64.sqrt();
+        assert_relative_eq!(r.min, Vector3::new(10.0 - s, -s, -1.0), epsilon = 1e-12);
+        assert_relative_eq!(r.max, Vector3::new(10.0 + s, s, 1.0), epsilon = 1e-12);
+
+        // Matches the box of the transformed corners.
+        let m =
+            Matrix3::translate(&Vector2::new(1.0, 2.0)) * Matrix3::scale(&Vector2::new(-2.0, 3.0));
+        let b = Aabb2::new(Vector2::new(1.0, 0.0), Vector2::new(2.0, 1.0));
+        let corners = [
+            Vector2::new(1.0, 0.0),
+            Vector2::new(2.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(2.0, 1.0),
+        ]
+        .map(|p| Matrix3::transform_point(&m, &p));
+        assert_eq!(
+            Aabb2::transform(&m, &b),
+            Aabb2::from_points(&corners).unwrap()
+        );
+    }
+}
@@ -0,0 +1,167 @@
+// Slice-level transforms. The matrix columns are read once per call instead of once per
+// element, and the loops are simple enough for the compiler to vectorize.
+
+use crate::matrix::Matrix4;
+use crate::scalar::{Real, Scalar};
+use crate::vector::{Vector3, Vector4};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+const PAR_CHUNK_LEN: usize = 4096;
+
+#[inline]
+fn truncate<T: Copy>(v: &Vector4<T>) -> Vector3<T> {
+    Vector3::new(v.x, v.y, v.z)
+}
+
+impl<T: Scalar> Matrix4<T> {
+    /// Applies `transform_point` to every element of `points`.
+    pub fn transform_points_in_place(m: &Matrix4<T>, points: &mut [Vector3<T>]) {
+        let (x, y, z, w) = (
+            truncate(&m.x),
+            truncate(&m.y),
+            truncate(&m.z),
+            truncate(&m.w),
+        );
+        for p in points {
+            *p = x * p.x + (y * p.y + (z * p.z + w));
+        }
+    }
+
+    /// Applies `transform_vector` to every element of `vectors`.
+    pub fn transform_vectors_in_place(m: &Matrix4<T>, vectors: &mut [Vector3<T>]) {
+        let (x, y, z) = (truncate(&m.x), truncate(&m.y), truncate(&m.z));
+        for v in vectors {
+            *v = x * v.x + (y * v.y + z * v.z);
+        }
+    }
+}
+
+impl<T: Real> Matrix4<T> {
+    /// Transforms surface normals by the inverse transpose of the upper-left 3x3 block of `m`
+    /// and renormalizes them, so normals stay perpendicular under non-uniform scaling.
+    pub fn transform_normals_in_place(m: &Matrix4<T>, normals: &mut [Vector3<T>]) {
+        // The cofactor matrix is the inverse transpose scaled by the determinant, only its
+        // sign matters after normalizing.
+        let (a, b, c) = (truncate(&m.x), truncate(&m.y), truncate(&m.z));
+        let (mut x, mut y, mut z) = (
+            Vector3::cross(&b, &c),
+            Vector3::cross(&c, &a),
+            Vector3::cross(&a, &b),
+        );
+        if Vector3::dot(&a, &x) < T::zero() {
+            x *= -T::one();
+            y *= -T::one();
+            z *= -T::one();
+        }
+        for n in normals {
+            *n = Vector3::normalize(&(x * n.x + (y * n.y + z * n.z)));
+        }
+    }
+}
+
+impl<T: Real> Vector3<T> {
+    pub fn normalize_all(vectors: &mut [Vector3<T>]) {
+        for v in vectors {
+            *v = Vector3::normalize(v);
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Scalar + Send + Sync> Matrix4<T> {
+    pub fn par_transform_points_in_place(m: &Matrix4<T>, points: &mut [Vector3<T>]) {
+        points
+            .par_chunks_mut(PAR_CHUNK_LEN)
+            .for_each(|c| Matrix4::transform_points_in_place(m, c));
+    }
+
+    pub fn par_transform_vectors_in_place(m: &Matrix4<T>, vectors: &mut [Vector3<T>]) {
+        vectors
+            .par_chunks_mut(PAR_CHUNK_LEN)
+            .for_each(|c| Matrix4::transform_vectors_in_place(m, c));
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Real + Send + Sync> Matrix4<T> {
+    pub fn par_transform_normals_in_place(m: &Matrix4<T>, normals: &mut [Vector3<T>]) {
+        normals
+            .par_chunks_mut(PAR_CHUNK_LEN)
+            .for_each(|c| Matrix4::transform_normals_in_place(m, c));
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Real + Send + Sync> Vector3<T> {
+    pub fn par_normalize_all(vectors: &mut [Vector3<T>]) {
+        vectors
+            .par_chunks_mut(PAR_CHUNK_LEN)
+            .for_each(Vector3::normalize_all);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(n: usize) -> Vec<Vector3<f64>> {
+        (0..n)
+            .map(|i| {
+                let i = i as f64;
+                Vector3::new(i.sin(), (2.0 * i).cos(), 0.5 - i)
+            })
+            .collect()
+    }
+
+    fn transform() -> Matrix4<f64> {
+        Matrix4::translate(&Vector3::new(1.0, -2.0, 3.0))
+            * Matrix4::rotate(0.5, &Vector3::normalize(&Vector3::new(1.0, 1.0, 0.0)))
+            * Matrix4::scale(&Vector3::new(2.0, 1.0, -0.5))
+    }
+
+    #[test]
+    fn matrix4_transform_points_and_vectors_in_place() {
+        let m = transform();
+        let src = points(37);
+        let mut p = src.clone();
+        let mut v = src.clone();
+        Matrix4::transform_points_in_place(&m, &mut p);
+        Matrix4::transform_vectors_in_place(&m, &mut v);
+        for i in 0..src.len() {
+            assert_relative_eq!(p[i], Matrix4::transform_point(&m, &src[i]));
+            assert_relative_eq!(v[i], Matrix4::transform_vector(&m, &src[i]));
+        }
+    }
+
+    #[test]
+    fn matrix4_transform_normals_in_place() {
+        let m = transform();
+        let src = points(37);
+        let mut n = src.clone();
+        Vector3::normalize_all(&mut n);
+        Matrix4::transform_normals_in_place(&m, &mut n);
+        let inverse = Matrix4::inverse(&m).unwrap();
+        let t = Matrix4::transpose(&inverse);
+        for i in 0..src.len() {
+            let expected = Vector3::normalize(&Matrix4::transform_vector(&t, &src[i]));
+            assert_relative_eq!(n[i], expected, epsilon = 1e-12);
+            // Still perpendicular to a transformed tangent.
+            let tangent = Vector3::cross(&src[i], &Vector3::new(0.0, 0.0, 1.0));
+            let tangent = Matrix4::transform_vector(&m, &tangent);
+            assert!(Vector3::dot(&n[i], &tangent).abs() < 1e-12);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn matrix4_par_transform_points_in_place() {
+        let m = transform();
+        let mut p = points(10_000);
+        let mut q = p.clone();
+        Matrix4::transform_points_in_place(&m, &mut p);
+        Matrix4::par_transform_points_in_place(&m, &mut q);
+        assert_eq!(p, q);
+    }
+}
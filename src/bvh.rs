@@ -0,0 +1,508 @@
+//! Bounding volume hierarchy over items given by their bounding boxes, for ray casts and
+//! box queries over many triangles or user primitives.
+//!
+//! Items are identified by their index in the slice the hierarchy was built from.
+
+use crate::aabb::Aabb3;
+use crate::ray::{Ray3, TriangleHit};
+use crate::scalar::Real;
+use crate::triangle::Triangle3;
+use crate::vector::*;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+// Leaves up to this size are kept whenever splitting them does not lower the cost.
+const MAX_LEAF_SIZE: usize = 4;
+
+// Nodes are stored depth first: an internal node's left child follows it and `first` is
+// its right child, a leaf holds the `count` items from `first` in `items`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Node<T> {
+    bounds: Aabb3<T>,
+    first: usize,
+    count: usize,
+}
+
+impl<T> Node<T> {
+    #[inline]
+    fn is_leaf(&self) -> bool {
+        self.count > 0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bvh<T> {
+    nodes: Vec<Node<T>>,
+    items: Vec<usize>,
+    bounds: Vec<Aabb3<T>>,
+}
+
+#[inline]
+fn component<T: Copy>(v: &Vector3<T>, i: usize) -> T {
+    [v.x, v.y, v.z][i]
+}
+
+impl<T: Real> Bvh<T> {
+    /// Builds the hierarchy over items with the given bounds, splitting nodes where the
+    /// surface area heuristic predicts the cheapest traversal, or in half where it would
+    /// rather keep a large leaf. Takes `O(n log² n)` time.
+    pub fn build(bounds: &[Aabb3<T>]) -> Bvh<T> {
+        let mut bvh = Bvh {
+            nodes: Vec::with_capacity(2 * bounds.len()),
+            items: (0..bounds.len()).collect(),
+            bounds: bounds.to_vec(),
+        };
+        if bounds.is_empty() {
+            return bvh;
+        }
+        let centers: Vec<_> = bounds.iter().map(Aabb3::center).collect();
+        let mut right_areas = Vec::with_capacity(bounds.len());
+        // Item ranges left to build, with the node they are the right child of. Left
+        // children are pushed last so they directly follow their parent.
+        let mut stack: Vec<(usize, usize, Option<usize>)> = Vec::with_capacity(64);
+        stack.push((0, bounds.len(), None));
+        while let Some((start, end, parent)) = stack.pop() {
+            let index = bvh.nodes.len();
+            if let Some(parent) = parent {
+                bvh.nodes[parent].first = index;
+            }
+            let items = &mut bvh.items[start..end];
+            let node_bounds = items[1..]
+                .iter()
+                .fold(bounds[items[0]], |b, &i| Aabb3::union(&b, &bounds[i]));
+            let split = Bvh::split(bounds, &centers, items, &node_bounds, &mut right_areas);
+            bvh.nodes.push(Node {
+                bounds: node_bounds,
+                first: start,
+                count: if split.is_some() { 0 } else { end - start },
+            });
+            if let Some(k) = split {
+                stack.push((start + k, end, Some(index)));
+                stack.push((start, start + k, None));
+            }
+        }
+        bvh
+    }
+
+    pub fn from_triangles(triangles: &[Triangle3<T>]) -> Bvh<T> {
+        let bounds: Vec<_> = triangles
+            .iter()
+            .map(|t| Aabb3::extend(&Aabb3::extend(&Aabb3::new(t.a, t.a), &t.b), &t.c))
+            .collect();
+        Bvh::build(&bounds)
+    }
+
+    // Orders `items` and returns how many go to the left child, or `None` for a leaf.
+    fn split(
+        bounds: &[Aabb3<T>],
+        centers: &[Vector3<T>],
+        items: &mut [usize],
+        node_bounds: &Aabb3<T>,
+        right_areas: &mut Vec<T>,
+    ) -> Option<usize> {
+        let n = items.len();
+        if n == 1 {
+            return None;
+        }
+        let (lo, hi) = items
+            .iter()
+            .fold((centers[items[0]], centers[items[0]]), |(lo, hi), &i| {
+                (
+                    Vector3::min(&lo, &centers[i]),
+                    Vector3::max(&hi, &centers[i]),
+                )
+            });
+        let spread = hi - lo;
+        if spread == Vector3::splat(T::zero()) {
+            // No axis separates coincident centers, so any split is as good as another.
+            return if n <= MAX_LEAF_SIZE {
+                None
+            } else {
+                Some(n / 2)
+            };
+        }
+
+        // Sweep the items sorted by center along each axis, costing every split by the
+        // areas and item counts of both sides. Ties go to the more balanced split.
+        let by_axis = |axis: usize| {
+            move |i: &usize, j: &usize| {
+                component(&centers[*i], axis)
+                    .partial_cmp(&component(&centers[*j], axis))
+                    .unwrap_or(Ordering::Equal)
+            }
+        };
+        let mut best: Option<(T, usize, usize)> = None;
+        for axis in 0..3 {
+            items.sort_by(by_axis(axis));
+            right_areas.clear();
+            right_areas.resize(n, T::zero());
+            let mut b = bounds[items[n - 1]];
+            for k in (1..n).rev() {
+                b = Aabb3::union(&b, &bounds[items[k]]);
+                right_areas[k] = Aabb3::surface_area(&b);
+            }
+            let mut b = bounds[items[0]];
+            let mut count = T::zero();
+            for k in 1..n {
+                b = Aabb3::union(&b, &bounds[items[k - 1]]);
+                count = count + T::one();
+                let right_count = T::from_f64((n - k) as f64);
+                let cost = Aabb3::surface_area(&b) * count + right_areas[k] * right_count;
+                let better = best.is_none_or(|(c, _, j)| {
+                    cost < c || (cost == c && k.abs_diff(n / 2) < j.abs_diff(n / 2))
+                });
+                if better {
+                    best = Some((cost, axis, k));
+                }
+            }
+        }
+        let (cost, axis, k) = best.unwrap();
+        // In units of one item test, a split costs one traversal step plus the expected
+        // tests below it, against testing all items here.
+        let area = Aabb3::surface_area(node_bounds);
+        if area + cost < T::from_f64(n as f64) * area {
+            if axis != 2 {
+                items.sort_by(by_axis(axis));
+            }
+            return Some(k);
+        }
+        if n <= MAX_LEAF_SIZE {
+            return None;
+        }
+        // Large leaves are slower than the heuristic predicts, so halve the items along
+        // the axis their centers spread the most.
+        let axis = if spread.x >= spread.y && spread.x >= spread.z {
+            0
+        } else if spread.y >= spread.z {
+            1
+        } else {
+            2
+        };
+        items.select_nth_unstable_by(n / 2, by_axis(axis));
+        Some(n / 2)
+    }
+
+    /// The bounds of all items, `None` if there are none.
+    #[inline]
+    pub fn bounds(bvh: &Bvh<T>) -> Option<Aabb3<T>> {
+        bvh.nodes.first().map(|n| n.bounds)
+    }
+
+    /// Updates the bounds after items moved, keeping the tree. Cheaper than rebuilding,
+    /// but traversal slows down as the items drift from where they were built. Panics if
+    /// `bounds` has a different length than the one built from.
+    pub fn refit(bvh: &mut Bvh<T>, bounds: &[Aabb3<T>]) {
+        bvh.bounds.copy_from_slice(bounds);
+        // Children come after their parents.
+        for i in (0..bvh.nodes.len()).rev() {
+            let node = bvh.nodes[i];
+            bvh.nodes[i].bounds = if node.is_leaf() {
+                let items = &bvh.items[node.first..node.first + node.count];
+                items[1..]
+                    .iter()
+                    .fold(bounds[items[0]], |b, &j| Aabb3::union(&b, &bounds[j]))
+            } else {
+                Aabb3::union(&bvh.nodes[i + 1].bounds, &bvh.nodes[node.first].bounds)
+            };
+        }
+    }
+
+    /// Calls `f` with each item whose bounds intersect `b`.
+    pub fn query_aabb<F: FnMut(usize)>(bvh: &Bvh<T>, b: &Aabb3<T>, mut f: F) {
+        if bvh.nodes.is_empty() {
+            return;
+        }
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0);
+        while let Some(i) = stack.pop() {
+            let node = &bvh.nodes[i];
+            if !Aabb3::intersects(&node.bounds, b) {
+                continue;
+            }
+            if node.is_leaf() {
+                for &item in &bvh.items[node.first..node.first + node.count] {
+                    if Aabb3::intersects(&bvh.bounds[item], b) {
+                        f(item);
+                    }
+                }
+            } else {
+                stack.push(node.first);
+                stack.push(i + 1);
+            }
+        }
+    }
+
+    /// The closest hit along the ray, with `intersect` testing an item and returning the
+    /// hit distance `t` with any data for the caller. Items are visited roughly front to
+    /// back, and only tested if the ray reaches their bounds before the closest hit so far.
+    pub fn closest_hit<H, F: FnMut(usize) -> Option<(T, H)>>(
+        bvh: &Bvh<T>,
+        r: &Ray3<T>,
+        mut intersect: F,
+    ) -> Option<(usize, H)> {
+        let inv = [0, 1, 2].map(|i| {
+            let d = component(&r.direction, i);
+            if d == T::zero() {
+                None
+            } else {
+                Some(d.recip())
+            }
+        });
+        let mut best: Option<(T, usize, H)> = None;
+        let mut stack = Vec::with_capacity(64);
+        if let Some(t) = bvh
+            .nodes
+            .first()
+            .and_then(|n| entry(r, &inv, &n.bounds, None))
+        {
+            stack.push((0, t));
+        }
+        while let Some((i, t)) = stack.pop() {
+            if best.as_ref().is_some_and(|b| t > b.0) {
+                continue;
+            }
+            let node = &bvh.nodes[i];
+            if node.is_leaf() {
+                for &item in &bvh.items[node.first..node.first + node.count] {
+                    let limit = best.as_ref().map(|b| b.0);
+                    if entry(r, &inv, &bvh.bounds[item], limit).is_none() {
+                        continue;
+                    }
+                    if let Some((t, hit)) = intersect(item) {
+                        if best.as_ref().is_none_or(|b| t < b.0) {
+                            best = Some((t, item, hit));
+                        }
+                    }
+                }
+                continue;
+            }
+            let limit = best.as_ref().map(|b| b.0);
+            let (a, b) = (i + 1, node.first);
+            let ta = entry(r, &inv, &bvh.nodes[a].bounds, limit);
+            let tb = entry(r, &inv, &bvh.nodes[b].bounds, limit);
+            // Push the farther child first so the nearer one is visited first.
+            match (ta, tb) {
+                (Some(ta), Some(tb)) if ta <= tb => stack.extend([(b, tb), (a, ta)]),
+                (Some(ta), Some(tb)) => stack.extend([(a, ta), (b, tb)]),
+                (Some(ta), None) => stack.push((a, ta)),
+                (None, Some(tb)) => stack.push((b, tb)),
+                (None, None) => {}
+            }
+        }
+        best.map(|(_, item, hit)| (item, hit))
+    }
+
+    /// The closest triangle hit by the ray, for a hierarchy built by `from_triangles`.
+    pub fn intersect_triangles(
+        bvh: &Bvh<T>,
+        triangles: &[Triangle3<T>],
+        r: &Ray3<T>,
+    ) -> Option<(usize, TriangleHit<T>)> {
+        Bvh::closest_hit(bvh, r, |i| {
+            let t = &triangles[i];
+            Ray3::intersect_triangle(r, &t.a, &t.b, &t.c).map(|hit| (hit.t, hit))
+        })
+    }
+}
+
+// The distance at which the ray enters `b`, zero if it starts inside, `None` if it misses
+// or only enters beyond `limit`. `inv` holds the inverse direction components.
+fn entry<T: Real>(r: &Ray3<T>, inv: &[Option<T>; 3], b: &Aabb3<T>, limit: Option<T>) -> Option<T> {
+    let mut near = T::zero();
+    let mut far = limit;
+    for (i, inv) in inv.iter().enumerate() {
+        let o = component(&r.origin, i);
+        let (lo, hi) = (component(&b.min, i), component(&b.max, i));
+        match *inv {
+            None => {
+                if o < lo || o > hi {
+                    return None;
+                }
+            }
+            Some(inv) => {
+                let (t0, t1) = ((lo - o) * inv, (hi - o) * inv);
+                let (t0, t1) = if inv > T::zero() { (t0, t1) } else { (t1, t0) };
+                if t0 > near {
+                    near = t0;
+                }
+                if far.is_none_or(|f| t1 < f) {
+                    far = Some(t1);
+                }
+            }
+        }
+    }
+    match far {
+        Some(far) if near > far => None,
+        _ => Some(near),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_triangles(rng: &mut SmallRng, n: usize) -> Vec<Triangle3<f64>> {
+        let point = |rng: &mut SmallRng, scale: f64| {
+            Vector3::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>()) * scale
+        };
+        (0..n)
+            .map(|_| {
+                let p = point(rng, 10.0);
+                Triangle3::new(p, p + point(rng, 1.0), p + point(rng, 1.0))
+            })
+            .collect()
+    }
+
+    fn brute_force(triangles: &[Triangle3<f64>], r: &Ray3<f64>) -> Option<(usize, f64)> {
+        triangles
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| Ray3::intersect_triangle(r, &t.a, &t.b, &t.c).map(|h| (i, h.t)))
+            .fold(None, |best: Option<(usize, f64)>, hit| match best {
+                Some(b) if b.1 <= hit.1 => Some(b),
+                _ => Some(hit),
+            })
+    }
+
+    fn check_structure(bvh: &Bvh<f64>, bounds: &[Aabb3<f64>]) {
+        let mut seen = vec![false; bounds.len()];
+        for (i, node) in bvh.nodes.iter().enumerate() {
+            if node.is_leaf() {
+                for &item in &bvh.items[node.first..node.first + node.count] {
+                    assert!(!seen[item]);
+                    seen[item] = true;
+                    assert!(Aabb3::contains_aabb(&node.bounds, &bounds[item]));
+                }
+            } else {
+                assert!(node.first > i + 1);
+                assert!(Aabb3::contains_aabb(&node.bounds, &bvh.nodes[i + 1].bounds));
+                assert!(Aabb3::contains_aabb(
+                    &node.bounds,
+                    &bvh.nodes[node.first].bounds
+                ));
+            }
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn bvh_build() {
+        let empty = Bvh::<f64>::build(&[]);
+        assert_eq!(Bvh::bounds(&empty), None);
+        let r = Ray3::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(Bvh::intersect_triangles(&empty, &[], &r), None);
+
+        let mut rng = SmallRng::seed_from_u64(1);
+        let triangles = random_triangles(&mut rng, 500);
+        let bvh = Bvh::from_triangles(&triangles);
+        let bounds: Vec<_> = triangles
+            .iter()
+            .map(|t| Aabb3::from_points(&[t.a, t.b, t.c]).unwrap())
+            .collect();
+        check_structure(&bvh, &bounds);
+        assert!(bvh.nodes.iter().all(|n| n.count <= MAX_LEAF_SIZE));
+
+        // Identical items still split down to small leaves.
+        let same = vec![bounds[0]; 40];
+        let bvh = Bvh::build(&same);
+        check_structure(&bvh, &same);
+        assert!(bvh.nodes.iter().all(|n| n.count <= MAX_LEAF_SIZE));
+
+        // Many coincident items, plus one elsewhere, split in half rather than one by one.
+        let mut same = vec![bounds[0]; 10_000];
+        same.push(Aabb3::new(Vector3::splat(20.0), Vector3::splat(21.0)));
+        let bvh = Bvh::build(&same);
+        check_structure(&bvh, &same);
+        assert!(bvh.nodes.iter().all(|n| n.count <= MAX_LEAF_SIZE));
+        let mut depth = vec![0; bvh.nodes.len()];
+        for (i, node) in bvh.nodes.iter().enumerate() {
+            if !node.is_leaf() {
+                depth[i + 1] = depth[i] + 1;
+                depth[node.first] = depth[i] + 1;
+            }
+        }
+        assert!(depth.iter().all(|&d| d <= 16));
+        let mut count = 0;
+        Bvh::query_aabb(&bvh, &bounds[0], |_| count += 1);
+        assert_eq!(count, 10_000);
+    }
+
+    #[test]
+    fn bvh_ray_and_box_queries() {
+        let mut rng = SmallRng::seed_from_u64(2);
+        let mut triangles = random_triangles(&mut rng, 300);
+        let mut bvh = Bvh::from_triangles(&triangles);
+        for round in 0..2 {
+            for _ in 0..300 {
+                let origin =
+                    Vector3::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>()) * 12.0;
+                let mut direction = Vector3::new(
+                    rng.gen::<f64>() - 0.5,
+                    rng.gen::<f64>() - 0.5,
+                    rng.gen::<f64>() - 0.5,
+                );
+                // Include rays along the axes, with zero direction components.
+                if rng.gen::<f64>() < 0.2 {
+                    direction = Vector3::new(direction.x, 0.0, 0.0);
+                }
+                let r = Ray3::new(origin, direction);
+                let expected = brute_force(&triangles, &r);
+                let hit = Bvh::intersect_triangles(&bvh, &triangles, &r).map(|(i, h)| (i, h.t));
+                assert_eq!(hit, expected);
+
+                let center = origin;
+                let query = Aabb3::new(center - Vector3::splat(1.0), center + Vector3::splat(1.0));
+                let mut found = Vec::new();
+                Bvh::query_aabb(&bvh, &query, |i| found.push(i));
+                found.sort_unstable();
+                let expected: Vec<_> = (0..triangles.len())
+                    .filter(|&i| {
+                        let t = &triangles[i];
+                        Aabb3::intersects(&Aabb3::from_points(&[t.a, t.b, t.c]).unwrap(), &query)
+                    })
+                    .collect();
+                assert_eq!(found, expected);
+            }
+            if round == 0 {
+                // Move every triangle and refit.
+                for t in triangles.iter_mut() {
+                    let d =
+                        Vector3::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>()) * 3.0;
+                    *t = Triangle3::new(t.a + d, t.b + d, t.c + d);
+                }
+                let bounds: Vec<_> = triangles
+                    .iter()
+                    .map(|t| Aabb3::from_points(&[t.a, t.b, t.c]).unwrap())
+                    .collect();
+                Bvh::refit(&mut bvh, &bounds);
+                check_structure(&bvh, &bounds);
+            }
+        }
+    }
+
+    #[test]
+    fn bvh_closest_hit_with_user_items() {
+        // Spheres along x, queried from inside the first one.
+        let centers: Vec<_> = (0..20)
+            .map(|i| Vector3::new(i as f64 * 3.0, 0.0, 0.0))
+            .collect();
+        let bounds: Vec<_> = centers
+            .iter()
+            .map(|c| Aabb3::new(*c - Vector3::splat(1.0), *c + Vector3::splat(1.0)))
+            .collect();
+        let bvh = Bvh::build(&bounds);
+        let r = Ray3::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        let mut tested = 0;
+        let hit = Bvh::closest_hit(&bvh, &r, |i| {
+            tested += 1;
+            Ray3::intersect_sphere(&r, &centers[i], 1.0).map(|h| (h.t, h.normal))
+        });
+        assert_eq!(hit, Some((0, Vector3::new(1.0, 0.0, 0.0))));
+        assert!(tested < centers.len());
+
+        let r = Ray3::new(Vector3::new(-5.0, 0.0, 0.0), Vector3::new(-1.0, 0.0, 0.0));
+        assert_eq!(Bvh::closest_hit(&bvh, &r, |_| Some((0.0, ()))), None);
+    }
+}
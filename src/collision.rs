@@ -0,0 +1,634 @@
+//! Convex collision detection: GJK for distances and closest points, EPA for penetration
+//! depth and contact normals, over shapes given by their support functions.
+//!
+//! Both work without allocating. Convergence is tested with a relative tolerance of
+//! `1e-6`, and iterations are bounded, so results are approximate for curved shapes.
+
+use crate::aabb::Aabb3;
+use crate::distance::{self, ClosestPoints};
+use crate::matrix::Matrix4;
+use crate::obb::Obb3;
+use crate::scalar::Real;
+use crate::sphere::Sphere;
+use crate::vector::*;
+
+/// A convex shape described by its support function.
+pub trait Support<T> {
+    /// A point of the shape furthest along `d`, which need not be normalized and may be
+    /// zero.
+    fn support(&self, d: &Vector3<T>) -> Vector3<T>;
+}
+
+impl<T, S: Support<T> + ?Sized> Support<T> for &S {
+    #[inline]
+    fn support(&self, d: &Vector3<T>) -> Vector3<T> {
+        (**self).support(d)
+    }
+}
+
+/// A single point.
+impl<T: Real> Support<T> for Vector3<T> {
+    #[inline]
+    fn support(&self, _: &Vector3<T>) -> Vector3<T> {
+        *self
+    }
+}
+
+impl<T: Real> Support<T> for Sphere<T> {
+    #[inline]
+    fn support(&self, d: &Vector3<T>) -> Vector3<T> {
+        let l = Vector3::length(d);
+        if l == T::zero() {
+            self.center
+        } else {
+            self.center + *d * (self.radius / l)
+        }
+    }
+}
+
+impl<T: Real> Support<T> for Aabb3<T> {
+    #[inline]
+    fn support(&self, d: &Vector3<T>) -> Vector3<T> {
+        let zero = T::zero();
+        let m = BVec3::new(d.x >= zero, d.y >= zero, d.z >= zero);
+        Vector3::select(&m, &self.max, &self.min)
+    }
+}
+
+impl<T: Real> Support<T> for Obb3<T> {
+    #[inline]
+    fn support(&self, d: &Vector3<T>) -> Vector3<T> {
+        let e = self.half_extents;
+        let side = |axis: &Vector3<T>, e: T| {
+            if Vector3::dot(axis, d) >= T::zero() {
+                *axis * e
+            } else {
+                *axis * -e
+            }
+        };
+        self.center + side(&self.axes.x, e.x) + side(&self.axes.y, e.y) + side(&self.axes.z, e.z)
+    }
+}
+
+/// The points within `radius` of the segment `a, b`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Capsule<T> {
+    pub a: Vector3<T>,
+    pub b: Vector3<T>,
+    pub radius: T,
+}
+
+impl<T> Capsule<T> {
+    #[inline]
+    pub fn new(a: Vector3<T>, b: Vector3<T>, radius: T) -> Capsule<T> {
+        Capsule { a, b, radius }
+    }
+}
+
+impl<T: Real> Support<T> for Capsule<T> {
+    #[inline]
+    fn support(&self, d: &Vector3<T>) -> Vector3<T> {
+        let end = if Vector3::dot(&self.a, d) >= Vector3::dot(&self.b, d) {
+            self.a
+        } else {
+            self.b
+        };
+        Sphere::new(end, self.radius).support(d)
+    }
+}
+
+/// The convex hull of a non-empty set of points.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ConvexHull<'a, T>(pub &'a [Vector3<T>]);
+
+impl<T: Real> Support<T> for ConvexHull<'_, T> {
+    fn support(&self, d: &Vector3<T>) -> Vector3<T> {
+        self.0[1..].iter().fold(self.0[0], |best, p| {
+            if Vector3::dot(p, d) > Vector3::dot(&best, d) {
+                *p
+            } else {
+                best
+            }
+        })
+    }
+}
+
+/// `shape` transformed by the affine `matrix`, typically a rigid transform placing it in
+/// the world.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transformed<T, S> {
+    pub shape: S,
+    pub matrix: Matrix4<T>,
+}
+
+impl<T, S> Transformed<T, S> {
+    #[inline]
+    pub fn new(shape: S, matrix: Matrix4<T>) -> Transformed<T, S> {
+        Transformed { shape, matrix }
+    }
+}
+
+impl<T: Real, S: Support<T>> Support<T> for Transformed<T, S> {
+    #[inline]
+    fn support(&self, d: &Vector3<T>) -> Vector3<T> {
+        // max over p of dot(d, M p) = max over p of dot(M^T d, p).
+        let local = Matrix4::transform_vector(&Matrix4::transpose(&self.matrix), d);
+        Matrix4::transform_point(&self.matrix, &self.shape.support(&local))
+    }
+}
+
+/// Penetration of two overlapping shapes. Moving the first shape by `-normal * depth`, or
+/// the second by `normal * depth`, brings them into touching contact. `a` is the point of
+/// the first shape deepest inside the second, `b` the reverse.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Contact<T> {
+    pub normal: Vector3<T>,
+    pub depth: T,
+    pub a: Vector3<T>,
+    pub b: Vector3<T>,
+}
+
+const MAX_ITERATIONS: usize = 64;
+const MAX_VERTICES: usize = 64;
+const MAX_FACES: usize = 2 * MAX_VERTICES;
+
+#[inline]
+fn tolerance<T: Real>() -> T {
+    T::from_f64(1e-6)
+}
+
+// A vertex of the Minkowski difference `a - b` with the points of `a` and `b` it came from.
+#[derive(Copy, Clone, Debug)]
+struct SupportPoint<T> {
+    w: Vector3<T>,
+    a: Vector3<T>,
+    b: Vector3<T>,
+}
+
+fn support_point<T: Real, A: Support<T>, B: Support<T>>(
+    a: &A,
+    b: &B,
+    d: &Vector3<T>,
+) -> SupportPoint<T> {
+    let pa = a.support(d);
+    let pb = b.support(&(*d * -T::one()));
+    SupportPoint {
+        w: pa - pb,
+        a: pa,
+        b: pb,
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Simplex<T> {
+    points: [SupportPoint<T>; 4],
+    len: usize,
+}
+
+impl<T: Real> Simplex<T> {
+    fn closest_points(&self, weights: &[T; 4]) -> ClosestPoints<T> {
+        let zero = Vector3::splat(T::zero());
+        let (a, b) = self.points[..self.len]
+            .iter()
+            .zip(weights)
+            .fold((zero, zero), |(a, b), (p, l)| (a + p.a * *l, b + p.b * *l));
+        let d = a - b;
+        ClosestPoints {
+            distance2: Vector3::dot(&d, &d),
+            a,
+            b,
+        }
+    }
+
+    // The point of the simplex closest to the origin and its barycentric weights, `None`
+    // if the simplex is a tetrahedron containing the origin.
+    fn closest_to_origin(&self) -> Option<(Vector3<T>, [T; 4])> {
+        let (zero, one) = (T::zero(), T::one());
+        let origin = Vector3::splat(zero);
+        let w: [Vector3<T>; 4] = [0, 1, 2, 3].map(|i| self.points[i].w);
+        match self.len {
+            1 => Some((w[0], [one, zero, zero, zero])),
+            2 => {
+                let (q, t) = distance::point_segment(&origin, &w[0], &w[1]);
+                Some((q.b, [one - t, t, zero, zero]))
+            }
+            3 => {
+                let (q, l) = distance::point_triangle(&origin, &w[0], &w[1], &w[2]);
+                Some((q.b, [l.x, l.y, l.z, zero]))
+            }
+            _ => {
+                const FACES: [[usize; 4]; 4] =
+                    [[0, 1, 2, 3], [0, 3, 1, 2], [0, 2, 3, 1], [1, 3, 2, 0]];
+                let mut inside = true;
+                let mut best: Option<(T, Vector3<T>, [T; 4])> = None;
+                for [i, j, k, l] in FACES {
+                    let n = Vector3::cross(&(w[j] - w[i]), &(w[k] - w[i]));
+                    let origin_side = -Vector3::dot(&n, &w[i]);
+                    let vertex_side = Vector3::dot(&n, &(w[l] - w[i]));
+                    if origin_side * vertex_side < zero || vertex_side == zero {
+                        inside = false;
+                    }
+                    let (q, b) = distance::point_triangle(&origin, &w[i], &w[j], &w[k]);
+                    if best.is_none_or(|(d, _, _)| q.distance2 < d) {
+                        let mut weights = [zero; 4];
+                        weights[i] = b.x;
+                        weights[j] = b.y;
+                        weights[k] = b.z;
+                        best = Some((q.distance2, q.b, weights));
+                    }
+                }
+                if inside {
+                    None
+                } else {
+                    best.map(|(_, v, weights)| (v, weights))
+                }
+            }
+        }
+    }
+
+    // Drops the points with zero weight.
+    fn reduce(&mut self, weights: &mut [T; 4]) {
+        let mut len = 0;
+        for i in 0..self.len {
+            if weights[i] > T::zero() {
+                self.points[len] = self.points[i];
+                weights[len] = weights[i];
+                len += 1;
+            }
+        }
+        self.len = len;
+    }
+}
+
+enum Gjk<T> {
+    Separated(ClosestPoints<T>),
+    Overlapping(Simplex<T>),
+}
+
+fn gjk<T: Real, A: Support<T>, B: Support<T>>(a: &A, b: &B) -> Gjk<T> {
+    let (zero, one) = (T::zero(), T::one());
+    let first = support_point(a, b, &Vector3::new(one, zero, zero));
+    let mut simplex = Simplex {
+        points: [first; 4],
+        len: 1,
+    };
+    let mut v = first.w;
+    let mut weights = [one, zero, zero, zero];
+    for _ in 0..MAX_ITERATIONS {
+        let vv = Vector3::dot(&v, &v);
+        // The origin within rounding error of the simplex counts as touching.
+        let scale = simplex.points[..simplex.len]
+            .iter()
+            .map(|p| Vector3::dot(&p.w, &p.w))
+            .fold(zero, |m, x| if x > m { x } else { m });
+        if vv <= tolerance::<T>() * tolerance::<T>() * scale {
+            return Gjk::Overlapping(simplex);
+        }
+        let p = support_point(a, b, &(v * -one));
+        if vv - Vector3::dot(&v, &p.w) <= tolerance::<T>() * vv {
+            break;
+        }
+        simplex.points[simplex.len] = p;
+        simplex.len += 1;
+        match simplex.closest_to_origin() {
+            None => return Gjk::Overlapping(simplex),
+            Some((closest, w)) => {
+                v = closest;
+                weights = w;
+                simplex.reduce(&mut weights);
+            }
+        }
+        if simplex.len == 4 {
+            // A degenerate tetrahedron with the origin on its boundary.
+            return Gjk::Overlapping(simplex);
+        }
+    }
+    Gjk::Separated(simplex.closest_points(&weights))
+}
+
+/// Closest points of two convex shapes, `None` if they overlap.
+pub fn closest_points<T: Real, A: Support<T>, B: Support<T>>(
+    a: &A,
+    b: &B,
+) -> Option<ClosestPoints<T>> {
+    match gjk(a, b) {
+        Gjk::Separated(q) => Some(q),
+        Gjk::Overlapping(_) => None,
+    }
+}
+
+#[inline]
+pub fn intersects<T: Real, A: Support<T>, B: Support<T>>(a: &A, b: &B) -> bool {
+    closest_points(a, b).is_none()
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Face<T> {
+    v: [usize; 3],
+    normal: Vector3<T>,
+    distance: T,
+}
+
+fn face<T: Real>(vertices: &[SupportPoint<T>], v: [usize; 3]) -> Option<Face<T>> {
+    let [a, b, c] = v.map(|i| vertices[i].w);
+    let n = Vector3::cross(&(b - a), &(c - a));
+    let l = Vector3::length(&n);
+    if l == T::zero() {
+        return None;
+    }
+    let normal = n * l.recip();
+    Some(Face {
+        v,
+        normal,
+        distance: Vector3::dot(&normal, &a),
+    })
+}
+
+// Grows the simplex GJK ended with to a tetrahedron, `None` if the Minkowski difference
+// is flat.
+fn tetrahedron<T: Real, A: Support<T>, B: Support<T>>(
+    a: &A,
+    b: &B,
+    mut simplex: Simplex<T>,
+) -> Option<[SupportPoint<T>; 4]> {
+    let (zero, one) = (T::zero(), T::one());
+    let axes = [
+        Vector3::new(one, zero, zero),
+        Vector3::new(zero, one, zero),
+        Vector3::new(zero, zero, one),
+    ];
+    let small = |x: T, scale: T| x.abs() <= tolerance::<T>() * scale;
+    while simplex.len < 4 {
+        let w = simplex.points.map(|p| p.w);
+        let candidates: [Vector3<T>; 6] = match simplex.len {
+            1 => [0, 1, 2, 3, 4, 5].map(|i| axes[i / 2] * if i % 2 == 0 { one } else { -one }),
+            2 => {
+                let d = w[1] - w[0];
+                let n1 = axes.iter().map(|axis| Vector3::cross(&d, axis)).fold(
+                    Vector3::splat(zero),
+                    |m, n| {
+                        if Vector3::dot(&n, &n) > Vector3::dot(&m, &m) {
+                            n
+                        } else {
+                            m
+                        }
+                    },
+                );
+                let n2 = Vector3::cross(&d, &n1);
+                [n1, n1 * -one, n2, n2 * -one, n1 + n2, (n1 + n2) * -one]
+            }
+            _ => {
+                let n = Vector3::cross(&(w[1] - w[0]), &(w[2] - w[0]));
+                [n, n * -one, n, n, n, n]
+            }
+        };
+        let mut grown = false;
+        for d in candidates.iter() {
+            let p = support_point(a, b, d);
+            let ok = match simplex.len {
+                1 => {
+                    let e = p.w - w[0];
+                    let s = Vector3::dot(&w[0], &w[0]) + Vector3::dot(&p.w, &p.w);
+                    !small(Vector3::dot(&e, &e), s)
+                }
+                2 => {
+                    let n = Vector3::cross(&(w[1] - w[0]), &(p.w - w[0]));
+                    let e = w[1] - w[0];
+                    !small(Vector3::length(&n), Vector3::dot(&e, &e))
+                }
+                _ => {
+                    let n = Vector3::cross(&(w[1] - w[0]), &(w[2] - w[0]));
+                    let e = p.w - w[0];
+                    let volume = Vector3::dot(&n, &e);
+                    !small(volume, Vector3::length(&n) * Vector3::length(&e))
+                }
+            };
+            if ok {
+                simplex.points[simplex.len] = p;
+                simplex.len += 1;
+                grown = true;
+                break;
+            }
+        }
+        if !grown {
+            return None;
+        }
+    }
+    Some(simplex.points)
+}
+
+/// Penetration depth, normal and contact points of two overlapping convex shapes, `None` if
+/// they are separated or the overlap is flat.
+pub fn penetration<T: Real, A: Support<T>, B: Support<T>>(a: &A, b: &B) -> Option<Contact<T>> {
+    let simplex = match gjk(a, b) {
+        Gjk::Separated(_) => return None,
+        Gjk::Overlapping(simplex) => simplex,
+    };
+    let start = tetrahedron(a, b, simplex)?;
+
+    let mut vertices = [start[0]; MAX_VERTICES];
+    vertices[..4].copy_from_slice(&start);
+    let mut vertex_count = 4;
+    let mut faces: [Option<Face<T>>; MAX_FACES] = [None; MAX_FACES];
+    let mut face_count = 0;
+    for [i, j, k, l] in [[0, 1, 2, 3], [0, 3, 1, 2], [0, 2, 3, 1], [1, 3, 2, 0]] {
+        // Wind each face outwards, away from the vertex opposite it.
+        let mut f = face(&vertices, [i, j, k])?;
+        if Vector3::dot(&f.normal, &(vertices[l].w - vertices[i].w)) > T::zero() {
+            f = face(&vertices, [i, k, j])?;
+        }
+        faces[face_count] = Some(f);
+        face_count += 1;
+    }
+
+    let mut closest = faces[0].unwrap();
+    for _ in 0..MAX_ITERATIONS {
+        closest = faces[..face_count]
+            .iter()
+            .flatten()
+            .fold(faces[0].unwrap(), |m, f| {
+                if f.distance < m.distance {
+                    *f
+                } else {
+                    m
+                }
+            });
+        let p = support_point(a, b, &closest.normal);
+        let d = Vector3::dot(&p.w, &closest.normal);
+        if d - closest.distance <= tolerance::<T>() * (d.abs() + closest.distance.abs())
+            || vertex_count == MAX_VERTICES
+        {
+            break;
+        }
+        vertices[vertex_count] = p;
+
+        // Remove the faces `p` sees, keeping the edges of the hole they leave.
+        let mut horizon = [(0, 0); MAX_FACES * 3];
+        let mut edge_count = 0;
+        let mut i = 0;
+        while i < face_count {
+            let f = faces[i].unwrap();
+            if Vector3::dot(&f.normal, &(p.w - vertices[f.v[0]].w)) > T::zero() {
+                for e in [(f.v[0], f.v[1]), (f.v[1], f.v[2]), (f.v[2], f.v[0])] {
+                    match horizon[..edge_count].iter().position(|&h| h == (e.1, e.0)) {
+                        Some(shared) => {
+                            edge_count -= 1;
+                            horizon[shared] = horizon[edge_count];
+                        }
+                        None => {
+                            horizon[edge_count] = e;
+                            edge_count += 1;
+                        }
+                    }
+                }
+                face_count -= 1;
+                faces[i] = faces[face_count];
+            } else {
+                i += 1;
+            }
+        }
+        if face_count + edge_count > MAX_FACES {
+            break;
+        }
+        for &(u, v) in &horizon[..edge_count] {
+            if let Some(f) = face(&vertices, [u, v, vertex_count]) {
+                faces[face_count] = Some(f);
+                face_count += 1;
+            }
+        }
+        vertex_count += 1;
+        if face_count == 0 {
+            break;
+        }
+    }
+
+    // The contact points from the projection of the origin onto the closest face.
+    let [i, j, k] = closest.v;
+    let (_, l) = distance::point_triangle(
+        &(closest.normal * closest.distance),
+        &vertices[i].w,
+        &vertices[j].w,
+        &vertices[k].w,
+    );
+    let point = |f: fn(&SupportPoint<T>) -> Vector3<T>| {
+        f(&vertices[i]) * l.x + f(&vertices[j]) * l.y + f(&vertices[k]) * l.z
+    };
+    Some(Contact {
+        normal: closest.normal,
+        depth: closest.distance,
+        a: point(|p| p.a),
+        b: point(|p| p.b),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    fn unit_box() -> Aabb3<f64> {
+        Aabb3::new(Vector3::new(-1.0, -1.0, -1.0), Vector3::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn gjk_distance() {
+        let a = Sphere::new(Vector3::new(0.0, 0.0, 0.0), 1.0);
+        let b = Sphere::new(Vector3::new(3.0, 4.0, 0.0), 2.0);
+        let q = closest_points(&a, &b).unwrap();
+        assert_relative_eq!(q.distance2.sqrt(), 2.0, epsilon = 1e-3);
+        assert_relative_eq!(q.a, Vector3::new(0.6, 0.8, 0.0), epsilon = 1e-3);
+        assert_relative_eq!(q.b, Vector3::new(1.8, 2.4, 0.0), epsilon = 1e-3);
+
+        // Box against capsule, exact for polytope-like features.
+        let c = Capsule::new(
+            Vector3::new(3.0, -5.0, 0.5),
+            Vector3::new(3.0, 5.0, 0.5),
+            0.5,
+        );
+        let q = closest_points(&unit_box(), &c).unwrap();
+        assert_relative_eq!(q.distance2, 1.5 * 1.5, epsilon = 1e-9);
+        assert_relative_eq!(q.a.x, 1.0, epsilon = 1e-9);
+        assert_relative_eq!(q.b.x, 2.5, epsilon = 1e-9);
+
+        // A hull against a point matches the direct distance query.
+        let tri = [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(0.0, 2.0, 0.0),
+        ];
+        let p = Vector3::new(0.5, 0.5, 1.5);
+        let q = closest_points(&ConvexHull(&tri), &p).unwrap();
+        let (expected, _) = distance::point_triangle(&p, &tri[0], &tri[1], &tri[2]);
+        assert_relative_eq!(q.distance2, expected.distance2, epsilon = 1e-9);
+        assert_relative_eq!(q.a, expected.b, epsilon = 1e-9);
+
+        assert!(intersects(&unit_box(), &Vector3::new(0.5, 0.5, 0.5)));
+        assert!(!intersects(&unit_box(), &Vector3::new(0.5, 1.5, 0.5)));
+    }
+
+    #[test]
+    fn gjk_transformed() {
+        // A unit box rotated 45 degrees about z reaches sqrt(2) along x.
+        let m = Matrix4::translate(&Vector3::new(3.0, 0.0, 0.0))
+            * Matrix4::rotate(std::f64::consts::FRAC_PI_4, &Vector3::new(0.0, 0.0, 1.0));
+        let rotated = Transformed::new(unit_box(), m);
+        let q = closest_points(&unit_box(), &rotated).unwrap();
+        assert_relative_eq!(q.distance2.sqrt(), 2.0 - 2.0f64.sqrt(), epsilon = 1e-9);
+        assert_relative_eq!(
+            q.b,
+            Vector3::new(3.0 - 2.0f64.sqrt(), 0.0, q.b.z),
+            epsilon = 1e-9
+        );
+
+        let m = Matrix4::translate(&Vector3::new(2.2, 0.0, 0.0))
+            * Matrix4::rotate(std::f64::consts::FRAC_PI_4, &Vector3::new(0.0, 0.0, 1.0));
+        assert!(intersects(&unit_box(), &Transformed::new(unit_box(), m)));
+    }
+
+    #[test]
+    fn epa_penetration() {
+        let a = unit_box();
+        let b = Aabb3::new(Vector3::new(0.7, -0.5, -0.5), Vector3::new(2.0, 0.5, 0.5));
+        assert!(closest_points(&a, &b).is_none());
+        let c = penetration(&a, &b).unwrap();
+        assert_relative_eq!(c.depth, 0.3, epsilon = 1e-9);
+        assert_relative_eq!(c.normal, Vector3::new(1.0, 0.0, 0.0), epsilon = 1e-9);
+        assert_relative_eq!(c.a.x, 1.0, epsilon = 1e-9);
+        assert_relative_eq!(c.b.x, 0.7, epsilon = 1e-9);
+
+        let s1 = Sphere::new(Vector3::new(0.0, 0.0, 0.0), 1.0);
+        let s2 = Sphere::new(Vector3::new(0.0, 1.5, 0.0), 1.0);
+        let c = penetration(&s1, &s2).unwrap();
+        assert_relative_eq!(c.depth, 0.5, epsilon = 1e-2);
+        assert_relative_eq!(c.normal, Vector3::new(0.0, 1.0, 0.0), epsilon = 1e-1);
+
+        // Rotated boxes stacked with a 0.25 overlap along y.
+        let m = Matrix4::translate(&Vector3::new(0.3, 1.75, 0.0))
+            * Matrix4::rotate(std::f64::consts::FRAC_PI_4, &Vector3::new(0.0, 1.0, 0.0));
+        let c = penetration(&Obb3::from_aabb(&a), &Transformed::new(a, m)).unwrap();
+        assert_relative_eq!(c.depth, 0.25, epsilon = 1e-9);
+        assert_relative_eq!(c.normal, Vector3::new(0.0, 1.0, 0.0), epsilon = 1e-9);
+
+        assert_eq!(penetration(&a, &Vector3::new(0.0, 3.0, 0.0)), None);
+
+        // Moving the second shape out by the penetration vector leaves them touching.
+        let mut rng = SmallRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let axis = Vector3::normalize(&Vector3::new(
+                rng.gen::<f64>() - 0.5,
+                rng.gen::<f64>() - 0.5,
+                rng.gen::<f64>() - 0.5,
+            ));
+            let offset = Vector3::new(rng.gen(), rng.gen(), rng.gen()) * 1.5;
+            let m = Matrix4::translate(&offset) * Matrix4::rotate(rng.gen::<f64>() * 3.0, &axis);
+            let b = Transformed::new(a, m);
+            if let Some(c) = penetration(&a, &b) {
+                let moved =
+                    Transformed::new(a, Matrix4::translate(&(c.normal * (c.depth + 1e-4))) * m);
+                let q = closest_points(&a, &moved).unwrap();
+                assert!(q.distance2.sqrt() < 2e-4);
+            }
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Closest points and squared distances between points, segments, triangles and boxes.
+//!
+//! Segments are given by their endpoints and parametrized as `a + t * (b - a)` for `t` in
+//! `0..=1`, triangles by their vertices. Degenerate segments and triangles are handled as the
+//! points or segments they collapse to.
+
+use crate::aabb::Aabb3;
+use crate::ray::Ray3;
+use crate::scalar::Real;
+use crate::vector::*;
+
+/// Closest points `a` on the first and `b` on the second primitive of a query.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClosestPoints<T> {
+    pub distance2: T,
+    pub a: Vector3<T>,
+    pub b: Vector3<T>,
+}
+
+impl<T: Real> ClosestPoints<T> {
+    #[inline]
+    fn new(a: Vector3<T>, b: Vector3<T>) -> ClosestPoints<T> {
+        let d = b - a;
+        ClosestPoints {
+            distance2: Vector3::dot(&d, &d),
+            a,
+            b,
+        }
+    }
+
+    #[inline]
+    fn swap(self) -> ClosestPoints<T> {
+        ClosestPoints {
+            a: self.b,
+            b: self.a,
+            ..self
+        }
+    }
+}
+
+#[inline]
+fn clamp01<T: Real>(v: T) -> T {
+    if v < T::zero() {
+        T::zero()
+    } else if v > T::one() {
+        T::one()
+    } else {
+        v
+    }
+}
+
+/// Closest points of `p` and the segment `a, b`, and the segment parameter.
+pub fn point_segment<T: Real>(
+    p: &Vector3<T>,
+    a: &Vector3<T>,
+    b: &Vector3<T>,
+) -> (ClosestPoints<T>, T) {
+    let ab = *b - *a;
+    let len2 = Vector3::dot(&ab, &ab);
+    let t = if len2 == T::zero() {
+        T::zero()
+    } else {
+        clamp01(Vector3::dot(&(*p - *a), &ab) / len2)
+    };
+    (ClosestPoints::new(*p, *a + ab * t), t)
+}
+
+/// Closest points of the segments `p1, q1` and `p2, q2`, and their parameters. Parallel
+/// segments at the same distance along an overlap return the points at the start of the
+/// first segment's part of it.
+pub fn segment_segment<T: Real>(
+    p1: &Vector3<T>,
+    q1: &Vector3<T>,
+    p2: &Vector3<T>,
+    q2: &Vector3<T>,
+) -> (ClosestPoints<T>, T, T) {
+    // Ericson, Real-Time Collision Detection, 5.1.9.
+    let d1 = *q1 - *p1;
+    let d2 = *q2 - *p2;
+    let r = *p1 - *p2;
+    let a = Vector3::dot(&d1, &d1);
+    let e = Vector3::dot(&d2, &d2);
+    let f = Vector3::dot(&d2, &r);
+    let zero = T::zero();
+
+    let (s, t) = if a == zero && e == zero {
+        (zero, zero)
+    } else if a == zero {
+        (zero, clamp01(f / e))
+    } else {
+        let c = Vector3::dot(&d1, &r);
+        if e == zero {
+            (clamp01(-c / a), zero)
+        } else {
+            let b = Vector3::dot(&d1, &d2);
+            let denom = a * e - b * b;
+            // Parallel segments, up to rounding: any `s` works, start from the first end.
+            let s = if denom > a * e * T::from_f64(1e-12) {
+                clamp01((b * f - c * e) / denom)
+            } else {
+                zero
+            };
+            // Closest point on the second line to `s`, clamped, then `s` recomputed for it.
+            let t = (b * s + f) / e;
+            if t < zero {
+                (clamp01(-c / a), zero)
+            } else if t > T::one() {
+                (clamp01((b - c) / a), T::one())
+            } else {
+                (s, t)
+            }
+        }
+    };
+    (ClosestPoints::new(*p1 + d1 * s, *p2 + d2 * t), s, t)
+}
+
+/// Closest points of `p` and the triangle `a, b, c`, and the barycentric coordinates of the
+/// latter.
+pub fn point_triangle<T: Real>(
+    p: &Vector3<T>,
+    a: &Vector3<T>,
+    b: &Vector3<T>,
+    c: &Vector3<T>,
+) -> (ClosestPoints<T>, Vector3<T>) {
+    // Ericson, 5.1.5: find the Voronoi region of `p` among vertices, edges and face.
+    let (zero, one) = (T::zero(), T::one());
+    let result = |v: T, w: T| {
+        let q = *a + (*b - *a) * v + (*c - *a) * w;
+        (ClosestPoints::new(*p, q), Vector3::new(one - v - w, v, w))
+    };
+    let ab = *b - *a;
+    let ac = *c - *a;
+    let ap = *p - *a;
+    let d1 = Vector3::dot(&ab, &ap);
+    let d2 = Vector3::dot(&ac, &ap);
+    if d1 <= zero && d2 <= zero {
+        return result(zero, zero);
+    }
+    let bp = *p - *b;
+    let d3 = Vector3::dot(&ab, &bp);
+    let d4 = Vector3::dot(&ac, &bp);
+    if d3 >= zero && d4 <= d3 {
+        return result(one, zero);
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= zero && d1 >= zero && d3 <= zero {
+        return result(d1 / (d1 - d3), zero);
+    }
+    let cp = *p - *c;
+    let d5 = Vector3::dot(&ab, &cp);
+    let d6 = Vector3::dot(&ac, &cp);
+    if d6 >= zero && d5 <= d6 {
+        return result(zero, one);
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= zero && d2 >= zero && d6 <= zero {
+        return result(zero, d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= zero && d4 - d3 >= zero && d5 - d6 >= zero {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return result(one - w, w);
+    }
+    let sum = va + vb + vc;
+    if sum == zero {
+        // Degenerate triangle: the closest of its edges.
+        let (ab, bc, ca) = (
+            point_segment(p, a, b),
+            point_segment(p, b, c),
+            point_segment(p, c, a),
+        );
+        return if ab.0.distance2 <= bc.0.distance2 && ab.0.distance2 <= ca.0.distance2 {
+            (ab.0, Vector3::new(one - ab.1, ab.1, zero))
+        } else if bc.0.distance2 <= ca.0.distance2 {
+            (bc.0, Vector3::new(zero, one - bc.1, bc.1))
+        } else {
+            (ca.0, Vector3::new(ca.1, zero, one - ca.1))
+        };
+    }
+    let denom = sum.recip();
+    result(vb * denom, vc * denom)
+}
+
+/// Closest points of `p` and the box `b`.
+#[inline]
+pub fn point_aabb<T: Real>(p: &Vector3<T>, b: &Aabb3<T>) -> ClosestPoints<T> {
+    ClosestPoints::new(*p, Aabb3::closest_point(b, p))
+}
+
+/// Closest points of two triangles. For intersecting triangles both points are the same
+/// point of the intersection.
+pub fn triangle_triangle<T: Real>(t1: &[Vector3<T>; 3], t2: &[Vector3<T>; 3]) -> ClosestPoints<T> {
+    let edges = |t: &[Vector3<T>; 3]| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])];
+
+    // An edge piercing the other triangle. Coplanar overlaps are found below, as a pair
+    // of crossing edges or a vertex inside the other triangle.
+    for (edges, t) in [(edges(t1), t2), (edges(t2), t1)] {
+        for (p, q) in edges.iter() {
+            let r = Ray3::new(*p, *q - *p);
+            if let Some(hit) = Ray3::intersect_triangle(&r, &t[0], &t[1], &t[2]) {
+                if hit.t <= T::one() {
+                    let x = Ray3::at(&r, hit.t);
+                    return ClosestPoints::new(x, x);
+                }
+            }
+        }
+    }
+
+    let mut best: Option<ClosestPoints<T>> = None;
+    let mut consider = |q: ClosestPoints<T>| {
+        if best.is_none_or(|b| q.distance2 < b.distance2) {
+            best = Some(q);
+        }
+    };
+    for (p1, q1) in edges(t1).iter() {
+        for (p2, q2) in edges(t2).iter() {
+            consider(segment_segment(p1, q1, p2, q2).0);
+        }
+    }
+    for p in t1 {
+        consider(point_triangle(p, &t2[0], &t2[1], &t2[2]).0);
+    }
+    for p in t2 {
+        consider(point_triangle(p, &t1[0], &t1[1], &t1[2]).0.swap());
+    }
+    best.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64, z: f64) -> Vector3<f64> {
+        Vector3::new(x, y, z)
+    }
+
+    #[test]
+    fn distance_point_segment() {
+        let (q, t) = point_segment(&v(1.0, 2.0, 0.0), &v(0.0, 0.0, 0.0), &v(4.0, 0.0, 0.0));
+        assert_eq!((q.distance2, q.b, t), (4.0, v(1.0, 0.0, 0.0), 0.25));
+        let (q, t) = point_segment(&v(-1.0, 0.0, 0.0), &v(0.0, 0.0, 0.0), &v(4.0, 0.0, 0.0));
+        assert_eq!((q.distance2, t), (1.0, 0.0));
+        // A segment collapsed to a point.
+        let (q, t) = point_segment(&v(0.0, 3.0, 4.0), &v(0.0, 0.0, 0.0), &v(0.0, 0.0, 0.0));
+        assert_eq!((q.distance2, t), (25.0, 0.0));
+    }
+
+    #[test]
+    fn distance_segment_segment() {
+        // Skew segments crossing at a distance of 1.
+        let (q, s, t) = segment_segment(
+            &v(-1.0, 0.0, 0.0),
+            &v(1.0, 0.0, 0.0),
+            &v(0.0, -1.0, 1.0),
+            &v(0.0, 3.0, 1.0),
+        );
+        assert_relative_eq!(q.distance2, 1.0);
+        assert_relative_eq!(q.a, v(0.0, 0.0, 0.0));
+        assert_relative_eq!(q.b, v(0.0, 0.0, 1.0));
+        assert_relative_eq!(Vector2::new(s, t), Vector2::new(0.5, 0.25));
+
+        // Parallel and overlapping.
+        let (q, s, t) = segment_segment(
+            &v(0.0, 0.0, 0.0),
+            &v(2.0, 0.0, 0.0),
+            &v(1.0, 1.0, 0.0),
+            &v(5.0, 1.0, 0.0),
+        );
+        assert_relative_eq!(q.distance2, 1.0);
+        assert_relative_eq!(q.b - q.a, v(0.0, 1.0, 0.0));
+        assert!((0.5..=1.0).contains(&s) && (0.0..=0.25).contains(&t));
+
+        // Parallel and disjoint along their line.
+        let (q, s, t) = segment_segment(
+            &v(0.0, 0.0, 0.0),
+            &v(1.0, 0.0, 0.0),
+            &v(3.0, 1.0, 0.0),
+            &v(2.0, 1.0, 0.0),
+        );
+        assert_relative_eq!(q.distance2, 2.0);
+        assert_relative_eq!(Vector2::new(s, t), Vector2::new(1.0, 1.0));
+
+        // Both degenerate.
+        let (q, _, _) = segment_segment(
+            &v(0.0, 0.0, 0.0),
+            &v(0.0, 0.0, 0.0),
+            &v(0.0, 2.0, 0.0),
+            &v(0.0, 2.0, 0.0),
+        );
+        assert_eq!(q.distance2, 4.0);
+    }
+
+    #[test]
+    fn distance_point_triangle() {
+        let (a, b, c) = (v(0.0, 0.0, 0.0), v(2.0, 0.0, 0.0), v(0.0, 2.0, 0.0));
+        let (q, bary) = point_triangle(&v(0.5, 0.5, 3.0), &a, &b, &c);
+        assert_relative_eq!(q.distance2, 9.0);
+        assert_relative_eq!(bary, v(0.5, 0.25, 0.25));
+        let (q, bary) = point_triangle(&v(-1.0, -1.0, 0.0), &a, &b, &c);
+        assert_relative_eq!(q.b, a);
+        assert_relative_eq!(bary, v(1.0, 0.0, 0.0));
+        let (q, bary) = point_triangle(&v(2.0, 2.0, 0.0), &a, &b, &c);
+        assert_relative_eq!(q.b, v(1.0, 1.0, 0.0));
+        assert_relative_eq!(bary, v(0.0, 0.5, 0.5));
+        // A triangle collapsed to a segment.
+        let (q, bary) = point_triangle(&v(1.0, 1.0, 0.0), &a, &b, &v(1.0, 0.0, 0.0));
+        assert_relative_eq!(q.distance2, 1.0);
+        assert_relative_eq!(q.b, v(1.0, 0.0, 0.0));
+        assert_relative_eq!(bary.x + bary.y + bary.z, 1.0);
+    }
+
+    #[test]
+    fn distance_point_aabb() {
+        let b = Aabb3::new(v(0.0, 0.0, 0.0), v(1.0, 1.0, 1.0));
+        let q = point_aabb(&v(2.0, 0.5, -1.0), &b);
+        assert_eq!((q.distance2, q.b), (2.0, v(1.0, 0.5, 0.0)));
+        assert_eq!(point_aabb(&v(0.5, 0.5, 0.5), &b).distance2, 0.0);
+    }
+
+    #[test]
+    fn distance_triangle_triangle() {
+        let t1 = [v(0.0, 0.0, 0.0), v(2.0, 0.0, 0.0), v(0.0, 2.0, 0.0)];
+        // Parallel, one above the other.
+        let t2 = [v(0.2, 0.2, 1.0), v(1.0, 0.2, 1.0), v(0.2, 1.0, 1.0)];
+        assert_relative_eq!(triangle_triangle(&t1, &t2).distance2, 1.0);
+        // Edge to edge.
+        let t2 = [v(2.0, 2.0, -1.0), v(2.0, 2.0, 1.0), v(3.0, 3.0, 0.0)];
+        let q = triangle_triangle(&t1, &t2);
+        assert_relative_eq!(q.distance2, 2.0);
+        assert_relative_eq!(q.a, v(1.0, 1.0, 0.0));
+        assert_relative_eq!(q.b, v(2.0, 2.0, 0.0));
+        // Piercing.
+        let t2 = [v(0.5, 0.5, -1.0), v(0.5, 0.5, 1.0), v(3.0, 3.0, 0.0)];
+        let q = triangle_triangle(&t1, &t2);
+        assert_eq!(q.distance2, 0.0);
+        assert_relative_eq!(q.a.z, 0.0);
+        // Coplanar and overlapping.
+        let t2 = [v(1.0, 1.0, 0.0), v(3.0, 1.0, 0.0), v(1.0, -1.0, 0.0)];
+        assert_eq!(triangle_triangle(&t1, &t2).distance2, 0.0);
+        assert_eq!(triangle_triangle(&t2, &t1).distance2, 0.0);
+    }
+}
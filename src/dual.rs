@@ -0,0 +1,485 @@
+use crate::scalar::{Real, Scalar, SignedScalar};
+use num_traits::{One, Zero};
+use core::cmp::Ordering;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Forward-mode dual number `re + du·ε` with `ε² = 0`.
+///
+/// Evaluating a function on `Dual::variable(x)` yields `f(x)` in `re` and `f'(x)` in `du`.
+/// Comparisons, including `==` and `is_zero`, only look at the real part.
+#[derive(Copy, Clone, Debug)]
+pub struct Dual<T> {
+    pub re: T,
+    pub du: T,
+}
+
+impl<T: Real> Dual<T> {
+    #[inline]
+    pub fn new(re: T, du: T) -> Dual<T> {
+        Dual { re, du }
+    }
+
+    #[inline]
+    pub fn constant(re: T) -> Dual<T> {
+        Dual::new(re, T::zero())
+    }
+
+    #[inline]
+    pub fn variable(re: T) -> Dual<T> {
+        Dual::new(re, T::one())
+    }
+}
+
+impl<T: Real> Add<Dual<T>> for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline]
+    fn add(self, rhs: Dual<T>) -> Self::Output {
+        Dual::new(self.re + rhs.re, self.du + rhs.du)
+    }
+}
+
+impl<T: Real> Sub<Dual<T>> for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline]
+    fn sub(self, rhs: Dual<T>) -> Self::Output {
+        Dual::new(self.re - rhs.re, self.du - rhs.du)
+    }
+}
+
+impl<T: Real> Mul<Dual<T>> for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline]
+    fn mul(self, rhs: Dual<T>) -> Self::Output {
+        Dual::new(self.re * rhs.re, self.du * rhs.re + self.re * rhs.du)
+    }
+}
+
+impl<T: Real> Div<Dual<T>> for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline]
+    fn div(self, rhs: Dual<T>) -> Self::Output {
+        let inv = rhs.re.recip();
+        let re = self.re * inv;
+        Dual::new(re, (self.du - re * rhs.du) * inv)
+    }
+}
+
+impl<T: Real> Neg for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Dual::new(-self.re, -self.du)
+    }
+}
+
+impl<T: Real> AddAssign<Dual<T>> for Dual<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Dual<T>) {
+        *self = *self + rhs
+    }
+}
+
+impl<T: Real> SubAssign<Dual<T>> for Dual<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Dual<T>) {
+        *self = *self - rhs
+    }
+}
+
+impl<T: Real> MulAssign<Dual<T>> for Dual<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Dual<T>) {
+        *self = *self * rhs
+    }
+}
+
+impl<T: Real> DivAssign<Dual<T>> for Dual<T> {
+    #[inline]
+    fn div_assign(&mut self, rhs: Dual<T>) {
+        *self = *self / rhs
+    }
+}
+
+impl<T: Real> PartialEq for Dual<T> {
+    #[inline]
+    fn eq(&self, other: &Dual<T>) -> bool {
+        self.re == other.re
+    }
+}
+
+impl<T: Real> PartialOrd for Dual<T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Dual<T>) -> Option<Ordering> {
+        self.re.partial_cmp(&other.re)
+    }
+}
+
+impl<T: Real> Zero for Dual<T> {
+    #[inline]
+    fn zero() -> Dual<T> {
+        Dual::constant(T::zero())
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.re.is_zero()
+    }
+}
+
+impl<T: Real> One for Dual<T> {
+    #[inline]
+    fn one() -> Dual<T> {
+        Dual::constant(T::one())
+    }
+}
+
+impl<T: Real> Scalar for Dual<T> {}
+
+impl<T: Real> SignedScalar for Dual<T> {}
+
+impl<T: Real> Real for Dual<T> {
+    #[inline]
+    fn from_f64(v: f64) -> Self {
+        Dual::constant(T::from_f64(v))
+    }
+
+    #[inline]
+    fn sqrt(self) -> Self {
+        let s = self.re.sqrt();
+        Dual::new(s, self.du / (s + s))
+    }
+
+    #[inline]
+    fn sin_cos(self) -> (Self, Self) {
+        let (s, c) = self.re.sin_cos();
+        (Dual::new(s, c * self.du), Dual::new(c, -s * self.du))
+    }
+
+    #[inline]
+    fn tan(self) -> Self {
+        let t = self.re.tan();
+        Dual::new(t, self.du * (T::one() + t * t))
+    }
+
+    #[inline]
+    fn recip(self) -> Self {
+        let inv = self.re.recip();
+        Dual::new(inv, -self.du * inv * inv)
+    }
+}
+
+/// Dual number carrying `N` partial derivatives, for gradients with respect to several
+/// variables in a single evaluation. Like `Dual`, comparisons only look at the real part.
+#[derive(Copy, Clone, Debug)]
+pub struct MultiDual<T, const N: usize> {
+    pub re: T,
+    pub du: [T; N],
+}
+
+impl<T: Real, const N: usize> MultiDual<T, N> {
+    #[inline]
+    pub fn new(re: T, du: [T; N]) -> MultiDual<T, N> {
+        MultiDual { re, du }
+    }
+
+    #[inline]
+    pub fn constant(re: T) -> MultiDual<T, N> {
+        MultiDual::new(re, [T::zero(); N])
+    }
+
+    /// Seeds the `i`-th partial derivative with one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= N`.
+    #[inline]
+    pub fn variable(re: T, i: usize) -> MultiDual<T, N> {
+        let mut du = [T::zero(); N];
+        du[i] = T::one();
+        MultiDual::new(re, du)
+    }
+
+    #[inline]
+    fn chain(re: T, d: T, du: &[T; N]) -> MultiDual<T, N> {
+        let mut out = [T::zero(); N];
+        for (o, &a) in out.iter_mut().zip(du.iter()) {
+            *o = d * a;
+        }
+        MultiDual::new(re, out)
+    }
+}
+
+impl<T: Real, const N: usize> Add<MultiDual<T, N>> for MultiDual<T, N> {
+    type Output = MultiDual<T, N>;
+
+    #[inline]
+    fn add(mut self, rhs: MultiDual<T, N>) -> Self::Output {
+        self.re = self.re + rhs.re;
+        for (a, &b) in self.du.iter_mut().zip(rhs.du.iter()) {
+            *a = *a + b;
+        }
+        self
+    }
+}
+
+impl<T: Real, const N: usize> Sub<MultiDual<T, N>> for MultiDual<T, N> {
+    type Output = MultiDual<T, N>;
+
+    #[inline]
+    fn sub(mut self, rhs: MultiDual<T, N>) -> Self::Output {
+        self.re = self.re - rhs.re;
+        for (a, &b) in self.du.iter_mut().zip(rhs.du.iter()) {
+            *a = *a - b;
+        }
+        self
+    }
+}
+
+impl<T: Real, const N: usize> Mul<MultiDual<T, N>> for MultiDual<T, N> {
+    type Output = MultiDual<T, N>;
+
+    #[inline]
+    fn mul(mut self, rhs: MultiDual<T, N>) -> Self::Output {
+        for (a, &b) in self.du.iter_mut().zip(rhs.du.iter()) {
+            *a = *a * rhs.re + self.re * b;
+        }
+        self.re = self.re * rhs.re;
+        self
+    }
+}
+
+impl<T: Real, const N: usize> Div<MultiDual<T, N>> for MultiDual<T, N> {
+    type Output = MultiDual<T, N>;
+
+    #[inline]
+    fn div(mut self, rhs: MultiDual<T, N>) -> Self::Output {
+        let inv = rhs.re.recip();
+        self.re = self.re * inv;
+        for (a, &b) in self.du.iter_mut().zip(rhs.du.iter()) {
+            *a = (*a - self.re * b) * inv;
+        }
+        self
+    }
+}
+
+impl<T: Real, const N: usize> Neg for MultiDual<T, N> {
+    type Output = MultiDual<T, N>;
+
+    #[inline]
+    fn neg(mut self) -> Self::Output {
+        self.re = -self.re;
+        for a in self.du.iter_mut() {
+            *a = -*a;
+        }
+        self
+    }
+}
+
+impl<T: Real, const N: usize> AddAssign<MultiDual<T, N>> for MultiDual<T, N> {
+    #[inline]
+    fn add_assign(&mut self, rhs: MultiDual<T, N>) {
+        *self = *self + rhs
+    }
+}
+
+impl<T: Real, const N: usize> SubAssign<MultiDual<T, N>> for MultiDual<T, N> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: MultiDual<T, N>) {
+        *self = *self - rhs
+    }
+}
+
+impl<T: Real, const N: usize> MulAssign<MultiDual<T, N>> for MultiDual<T, N> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: MultiDual<T, N>) {
+        *self = *self * rhs
+    }
+}
+
+impl<T: Real, const N: usize> DivAssign<MultiDual<T, N>> for MultiDual<T, N> {
+    #[inline]
+    fn div_assign(&mut self, rhs: MultiDual<T, N>) {
+        *self = *self / rhs
+    }
+}
+
+impl<T: Real, const N: usize> PartialEq for MultiDual<T, N> {
+    #[inline]
+    fn eq(&self, other: &MultiDual<T, N>) -> bool {
+        self.re == other.re
+    }
+}
+
+impl<T: Real, const N: usize> PartialOrd for MultiDual<T, N> {
+    #[inline]
+    fn partial_cmp(&self, other: &MultiDual<T, N>) -> Option<Ordering> {
+        self.re.partial_cmp(&other.re)
+    }
+}
+
+impl<T: Real, const N: usize> Zero for MultiDual<T, N> {
+    #[inline]
+    fn zero() -> MultiDual<T, N> {
+        MultiDual::constant(T::zero())
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.re.is_zero()
+    }
+}
+
+impl<T: Real, const N: usize> One for MultiDual<T, N> {
+    #[inline]
+    fn one() -> MultiDual<T, N> {
+        MultiDual::constant(T::one())
+    }
+}
+
+impl<T: Real, const N: usize> Scalar for MultiDual<T, N> {}
+
+impl<T: Real, const N: usize> SignedScalar for MultiDual<T, N> {}
+
+impl<T: Real, const N: usize> Real for MultiDual<T, N> {
+    #[inline]
+    fn from_f64(v: f64) -> Self {
+        MultiDual::constant(T::from_f64(v))
+    }
+
+    #[inline]
+    fn sqrt(self) -> Self {
+        let s = self.re.sqrt();
+        MultiDual::chain(s, (s + s).recip(), &self.du)
+    }
+
+    #[inline]
+    fn sin_cos(self) -> (Self, Self) {
+        let (s, c) = self.re.sin_cos();
+        (
+            MultiDual::chain(s, c, &self.du),
+            MultiDual::chain(c, -s, &self.du),
+        )
+    }
+
+    #[inline]
+    fn tan(self) -> Self {
+        let t = self.re.tan();
+        MultiDual::chain(t, T::one() + t * t, &self.du)
+    }
+
+    #[inline]
+    fn recip(self) -> Self {
+        let inv = self.re.recip();
+        MultiDual::chain(inv, -inv * inv, &self.du)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::*;
+    use crate::vector::*;
+
+    const H: f64 = 1e-6;
+
+    fn projected<T: Real>(fovy: T, angle: T) -> Vector4<T> {
+        let c = |v: f64| T::from_f64(v);
+        let proj = Matrix4::perspective(fovy, c(1.5), c(0.1), c(100.0));
+        let rot = Matrix4::rotate(angle, &Vector3::new(c(1.0), c(2.0), c(3.0)));
+        proj * rot * Vector4::new(c(0.5), c(-1.0), c(-4.0), c(1.0))
+    }
+
+    fn central_difference<F: Fn(f64) -> Vector4<f64>>(f: F, x: f64) -> Vector4<f64> {
+        (f(x + H) - f(x - H)) * (0.5 / H)
+    }
+
+    fn derivative(v: &Vector4<Dual<f64>>) -> Vector4<f64> {
+        Vector4::new(v.x.du, v.y.du, v.z.du, v.w.du)
+    }
+
+    #[test]
+    fn dual_arithmetic() {
+        let x = Dual::variable(3.0);
+        let y = x * x + Dual::constant(2.0) * x;
+        assert_eq!(y.re, 15.0);
+        assert_eq!(y.du, 8.0);
+        let z = Dual::constant(1.0) / x;
+        assert_ulps_eq!(z.du, -1.0 / 9.0);
+        let w = Dual::new(0.0, 1.0);
+        assert!(w == Dual::zero() && w.is_zero());
+    }
+
+    #[test]
+    fn dual_sqrt_sin_cos_tan() {
+        let x = Dual::variable(0.7f64);
+        assert_ulps_eq!(x.sqrt().du, 0.5 / 0.7f64.sqrt());
+        let (s, c) = x.sin_cos();
+        assert_ulps_eq!(s.du, 0.7f64.cos());
+        assert_ulps_eq!(c.du, -0.7f64.sin());
+        assert_ulps_eq!(x.tan().du, 1.0 / (0.7f64.cos() * 0.7f64.cos()));
+    }
+
+    #[test]
+    fn dual_vector3_length() {
+        let v = Vector3::new(
+            Dual::variable(3.0),
+            Dual::constant(4.0),
+            Dual::constant(0.0),
+        );
+        let l = Vector3::length(&v);
+        assert_eq!(l.re, 5.0);
+        assert_ulps_eq!(l.du, 0.6);
+    }
+
+    #[test]
+    fn dual_perspective_rotate_angle() {
+        let fovy = 1.2;
+        let angle = 0.4;
+        let d = projected(Dual::constant(fovy), Dual::variable(angle));
+        let fd = central_difference(|a| projected(fovy, a), angle);
+        assert_relative_eq!(derivative(&d), fd, epsilon = 1e-7, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn dual_perspective_rotate_fovy() {
+        let fovy = 1.2;
+        let angle = 0.4;
+        let d = projected(Dual::variable(fovy), Dual::constant(angle));
+        let fd = central_difference(|f| projected(f, angle), fovy);
+        assert_relative_eq!(derivative(&d), fd, epsilon = 1e-7, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn multi_dual_gradient() {
+        let fovy = 1.2;
+        let angle = 0.4;
+        let v = projected(
+            MultiDual::<f64, 2>::variable(fovy, 0),
+            MultiDual::<f64, 2>::variable(angle, 1),
+        );
+        let d_fovy = central_difference(|f| projected(f, angle), fovy);
+        let d_angle = central_difference(|a| projected(fovy, a), angle);
+        let partial = |i: usize| Vector4::new(v.x.du[i], v.y.du[i], v.z.du[i], v.w.du[i]);
+        assert_relative_eq!(partial(0), d_fovy, epsilon = 1e-7, max_relative = 1e-6);
+        assert_relative_eq!(partial(1), d_angle, epsilon = 1e-7, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn multi_dual_inverse() {
+        let m = Matrix2::new(
+            Vector2::new(
+                MultiDual::<f64, 1>::variable(2.0, 0),
+                MultiDual::constant(1.0),
+            ),
+            Vector2::new(MultiDual::constant(1.0), MultiDual::constant(3.0)),
+        );
+        let inv = Matrix2::inverse(&m).unwrap();
+        // d(det)/dm00 = m11 = 3, det = 5, so d(inv11)/dm00 = d(m00/det) = (det - 3 m00) / det^2
+        assert_ulps_eq!(inv.y.y.du[0], (5.0 - 6.0) / 25.0);
+    }
+}
@@ -0,0 +1,436 @@
+use crate::scalar::{Real, Scalar, SignedScalar};
+use approx::AbsDiffEq;
+use num_traits::{One, Zero};
+use core::fmt;
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+
+// Trigonometry is evaluated on i128 values with 60 fractional bits, independently of the
+// target type, so results only depend on integer arithmetic.
+const Q60: u32 = 60;
+const HALF_PI_Q60: i128 = 1_811_004_864_519_280_711;
+
+fn sin_cos_q60(x: i128) -> (i128, i128) {
+    let k = (x + HALF_PI_Q60 / 2).div_euclid(HALF_PI_Q60);
+    let r = x - k * HALF_PI_Q60;
+    let r2 = (r * r) >> Q60;
+
+    let mut s = r;
+    let mut term = r;
+    let mut n = 1;
+    while term != 0 {
+        term = -((term * r2) >> Q60) / ((n + 1) * (n + 2));
+        s += term;
+        n += 2;
+    }
+
+    let mut c = 1 << Q60;
+    let mut term = c;
+    let mut n = 0;
+    while term != 0 {
+        term = -((term * r2) >> Q60) / ((n + 1) * (n + 2));
+        c += term;
+        n += 2;
+    }
+
+    match k.rem_euclid(4) {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    }
+}
+
+fn isqrt(n: u128) -> u128 {
+    let mut x = n;
+    let mut r = 0;
+    let mut bit = 1 << 126;
+    while bit > n {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if x >= r + bit {
+            x -= r + bit;
+            r = (r >> 1) + bit;
+        } else {
+            r >>= 1;
+        }
+        bit >>= 2;
+    }
+    r
+}
+
+macro_rules! generate_fixed {
+    ($Fixed: ident, $Bits: ty, $Wide: ty, $frac: expr) => {
+        /// Signed fixed point number with `FRAC_BITS` fractional bits.
+        ///
+        /// Arithmetic saturates at `MIN` and `MAX` instead of overflowing, in debug and
+        /// release builds alike. Division or remainder by zero panics.
+        #[repr(transparent)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $Fixed($Bits);
+
+        impl $Fixed {
+            pub const FRAC_BITS: u32 = $frac;
+            pub const MIN: $Fixed = $Fixed(<$Bits>::MIN);
+            pub const MAX: $Fixed = $Fixed(<$Bits>::MAX);
+            pub const EPSILON: $Fixed = $Fixed(1);
+
+            #[inline]
+            pub const fn from_bits(bits: $Bits) -> $Fixed {
+                $Fixed(bits)
+            }
+
+            #[inline]
+            pub const fn to_bits(self) -> $Bits {
+                self.0
+            }
+
+            #[inline]
+            pub const fn from_int(v: $Bits) -> $Fixed {
+                $Fixed(v << $frac)
+            }
+
+            pub fn from_f64(v: f64) -> $Fixed {
+                let x = v * (1u64 << $frac) as f64;
+                let x = if x < 0.0 { x - 0.5 } else { x + 0.5 };
+                $Fixed(x as $Bits)
+            }
+
+            #[inline]
+            pub fn to_f64(self) -> f64 {
+                self.0 as f64 / (1u64 << $frac) as f64
+            }
+
+            #[inline]
+            pub fn from_f32(v: f32) -> $Fixed {
+                $Fixed::from_f64(v as f64)
+            }
+
+            #[inline]
+            pub fn to_f32(self) -> f32 {
+                self.to_f64() as f32
+            }
+
+            #[inline]
+            fn saturate(v: $Wide) -> $Fixed {
+                if v > <$Bits>::MAX as $Wide {
+                    $Fixed::MAX
+                } else if v < <$Bits>::MIN as $Wide {
+                    $Fixed::MIN
+                } else {
+                    $Fixed(v as $Bits)
+                }
+            }
+
+            #[inline]
+            fn from_q60(v: i128) -> $Fixed {
+                let shift = Q60 - $frac;
+                $Fixed(((v + (1 << (shift - 1))) >> shift) as $Bits)
+            }
+        }
+
+        impl Add<$Fixed> for $Fixed {
+            type Output = $Fixed;
+
+            #[inline]
+            fn add(self, rhs: $Fixed) -> Self::Output {
+                $Fixed(self.0.saturating_add(rhs.0))
+            }
+        }
+
+        impl Sub<$Fixed> for $Fixed {
+            type Output = $Fixed;
+
+            #[inline]
+            fn sub(self, rhs: $Fixed) -> Self::Output {
+                $Fixed(self.0.saturating_sub(rhs.0))
+            }
+        }
+
+        impl Mul<$Fixed> for $Fixed {
+            type Output = $Fixed;
+
+            #[inline]
+            fn mul(self, rhs: $Fixed) -> Self::Output {
+                let p = self.0 as $Wide * rhs.0 as $Wide;
+                $Fixed::saturate((p + (1 << ($frac - 1))) >> $frac)
+            }
+        }
+
+        impl Div<$Fixed> for $Fixed {
+            type Output = $Fixed;
+
+            #[inline]
+            fn div(self, rhs: $Fixed) -> Self::Output {
+                $Fixed::saturate(((self.0 as $Wide) << $frac) / rhs.0 as $Wide)
+            }
+        }
+
+        impl Rem<$Fixed> for $Fixed {
+            type Output = $Fixed;
+
+            #[inline]
+            fn rem(self, rhs: $Fixed) -> Self::Output {
+                $Fixed(self.0.wrapping_rem(rhs.0))
+            }
+        }
+
+        impl Neg for $Fixed {
+            type Output = $Fixed;
+
+            #[inline]
+            fn neg(self) -> Self::Output {
+                $Fixed(self.0.saturating_neg())
+            }
+        }
+
+        impl AddAssign<$Fixed> for $Fixed {
+            #[inline]
+            fn add_assign(&mut self, rhs: $Fixed) {
+                *self = *self + rhs
+            }
+        }
+
+        impl SubAssign<$Fixed> for $Fixed {
+            #[inline]
+            fn sub_assign(&mut self, rhs: $Fixed) {
+                *self = *self - rhs
+            }
+        }
+
+        impl MulAssign<$Fixed> for $Fixed {
+            #[inline]
+            fn mul_assign(&mut self, rhs: $Fixed) {
+                *self = *self * rhs
+            }
+        }
+
+        impl DivAssign<$Fixed> for $Fixed {
+            #[inline]
+            fn div_assign(&mut self, rhs: $Fixed) {
+                *self = *self / rhs
+            }
+        }
+
+        impl RemAssign<$Fixed> for $Fixed {
+            #[inline]
+            fn rem_assign(&mut self, rhs: $Fixed) {
+                *self = *self % rhs
+            }
+        }
+
+        impl Zero for $Fixed {
+            #[inline]
+            fn zero() -> $Fixed {
+                $Fixed(0)
+            }
+
+            #[inline]
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+        }
+
+        impl One for $Fixed {
+            #[inline]
+            fn one() -> $Fixed {
+                $Fixed(1 << $frac)
+            }
+        }
+
+        impl Scalar for $Fixed {}
+
+        impl SignedScalar for $Fixed {
+            #[inline]
+            fn abs(self) -> Self {
+                $Fixed(self.0.saturating_abs())
+            }
+        }
+
+        impl Real for $Fixed {
+            #[inline]
+            fn from_f64(v: f64) -> Self {
+                $Fixed::from_f64(v)
+            }
+
+            /// Rounds towards zero; negative inputs yield zero.
+            fn sqrt(self) -> Self {
+                if self.0 <= 0 {
+                    $Fixed(0)
+                } else {
+                    $Fixed(isqrt((self.0 as u128) << $frac) as $Bits)
+                }
+            }
+
+            fn sin_cos(self) -> (Self, Self) {
+                let (s, c) = sin_cos_q60((self.0 as i128) << (Q60 - $frac));
+                ($Fixed::from_q60(s), $Fixed::from_q60(c))
+            }
+
+            /// Saturates to `MIN`/`MAX` near the poles.
+            fn tan(self) -> Self {
+                let (s, c) = sin_cos_q60((self.0 as i128) << (Q60 - $frac));
+                if c == 0 {
+                    return if s < 0 { $Fixed::MIN } else { $Fixed::MAX };
+                }
+                let t = (s << $frac) / c;
+                if t > <$Bits>::MAX as i128 {
+                    $Fixed::MAX
+                } else if t < <$Bits>::MIN as i128 {
+                    $Fixed::MIN
+                } else {
+                    $Fixed(t as $Bits)
+                }
+            }
+        }
+
+        impl AbsDiffEq for $Fixed {
+            type Epsilon = $Fixed;
+
+            #[inline]
+            fn default_epsilon() -> $Fixed {
+                $Fixed::EPSILON
+            }
+
+            #[inline]
+            fn abs_diff_eq(&self, other: &Self, epsilon: $Fixed) -> bool {
+                (*self - *other).abs() <= epsilon
+            }
+        }
+
+        impl fmt::Display for $Fixed {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.to_f64(), f)
+            }
+        }
+    };
+}
+
+generate_fixed!(I16F16, i32, i64, 16);
+generate_fixed!(I32F32, i64, i128, 32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::*;
+    use crate::vector::*;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    #[test]
+    fn i16f16_arithmetic() {
+        let a = I16F16::from_f64(1.5);
+        let b = I16F16::from_f64(-2.25);
+        assert_eq!(a + b, I16F16::from_f64(-0.75));
+        assert_eq!(a - b, I16F16::from_f64(3.75));
+        assert_eq!(a * b, I16F16::from_f64(-3.375));
+        assert_eq!(b / a, I16F16::from_f64(-1.5));
+        assert_eq!(I16F16::from_int(3).to_bits(), 3 << 16);
+    }
+
+    #[test]
+    fn fixed_arithmetic_saturates() {
+        let big = I16F16::from_int(200);
+        assert_eq!(big * big, I16F16::MAX);
+        assert_eq!(big * -big, I16F16::MIN);
+        assert_eq!(big / I16F16::from_bits(1), I16F16::MAX);
+        assert_eq!(I16F16::MAX + I16F16::one(), I16F16::MAX);
+        assert_eq!(I16F16::MIN - I16F16::one(), I16F16::MIN);
+        assert_eq!(-I16F16::MIN, I16F16::MAX);
+        assert_eq!(I16F16::MIN.abs(), I16F16::MAX);
+        assert_eq!(I16F16::MIN % -I16F16::EPSILON, I16F16::zero());
+        let big = I32F32::from_int(1 << 20);
+        assert_eq!(big * big, I32F32::MAX);
+        assert_eq!(-big * big, I32F32::MIN);
+    }
+
+    #[test]
+    fn i32f32_arithmetic() {
+        let a = I32F32::from_f64(1.5);
+        let b = I32F32::from_f64(-2.25);
+        assert_eq!(a * b, I32F32::from_f64(-3.375));
+        assert_eq!(b / a, I32F32::from_f64(-1.5));
+    }
+
+    #[test]
+    fn i16f16_sqrt() {
+        assert_eq!(I16F16::from_int(4).sqrt(), I16F16::from_int(2));
+        assert_eq!(I16F16::from_int(2).sqrt().to_bits(), 92681);
+        assert_eq!(I16F16::from_int(-1).sqrt(), I16F16::zero());
+    }
+
+    #[test]
+    fn i32f32_sqrt() {
+        assert_eq!(I32F32::from_int(9).sqrt(), I32F32::from_int(3));
+        assert_abs_diff_eq!(
+            I32F32::from_int(2).sqrt(),
+            I32F32::from_f64(2f64.sqrt()),
+            epsilon = I32F32::EPSILON
+        );
+    }
+
+    #[test]
+    fn i16f16_sin_cos() {
+        for &x in &[0.0, 0.5, FRAC_PI_4, FRAC_PI_2, 2.0, PI, -1.0, -4.0, 10.0] {
+            let (s, c) = I16F16::from_f64(x).sin_cos();
+            let (es, ec) = I16F16::from_f64(x).to_f64().sin_cos();
+            assert_abs_diff_eq!(s, I16F16::from_f64(es), epsilon = I16F16::EPSILON);
+            assert_abs_diff_eq!(c, I16F16::from_f64(ec), epsilon = I16F16::EPSILON);
+        }
+    }
+
+    #[test]
+    fn i32f32_sin_cos() {
+        for &x in &[0.0, 0.5, FRAC_PI_4, FRAC_PI_2, 2.0, PI, -1.0, -4.0, 10.0] {
+            let (s, c) = I32F32::from_f64(x).sin_cos();
+            let (es, ec) = I32F32::from_f64(x).to_f64().sin_cos();
+            let eps = I32F32::from_bits(4);
+            assert_abs_diff_eq!(s, I32F32::from_f64(es), epsilon = eps);
+            assert_abs_diff_eq!(c, I32F32::from_f64(ec), epsilon = eps);
+        }
+    }
+
+    #[test]
+    fn i16f16_tan() {
+        let t = I16F16::from_f64(FRAC_PI_4).tan();
+        assert_abs_diff_eq!(t, I16F16::one(), epsilon = I16F16::EPSILON);
+        assert_eq!(I16F16::from_bits(102_943).tan(), I16F16::MAX);
+        assert_eq!(I16F16::from_bits(102_944).tan(), I16F16::MIN);
+    }
+
+    #[test]
+    fn matrix4_rotate_i16f16() {
+        let one = I16F16::one();
+        let zero = I16F16::zero();
+        let angle = I16F16::from_f64(FRAC_PI_2);
+        let r = Matrix4::rotate(angle, &Vector3::new(zero, zero, one));
+        let p = r * Vector4::new(one, zero, zero, one);
+        let eps = I16F16::from_bits(2);
+        assert_abs_diff_eq!(p, Vector4::new(zero, one, zero, one), epsilon = eps);
+    }
+
+    #[test]
+    fn matrix4_rotate_i32f32_deterministic() {
+        let axis = Vector3::new(
+            I32F32::from_int(1),
+            I32F32::from_int(2),
+            I32F32::from_int(3),
+        );
+        let r = Matrix4::rotate(I32F32::from_f64(0.75), &axis);
+        assert_eq!(r.x.x.to_bits(), 3_224_893_158);
+        assert_eq!(r.y.z.to_bits(), 1_276_318_707);
+    }
+
+    #[test]
+    fn matrix4_perspective_i32f32() {
+        let p = Matrix4::perspective(
+            I32F32::from_f64(FRAC_PI_2),
+            I32F32::from_int(2),
+            I32F32::from_int(1),
+            I32F32::from_int(100),
+        );
+        assert_abs_diff_eq!(p.x.x, I32F32::from_f64(0.5), epsilon = I32F32::from_bits(4));
+        assert_abs_diff_eq!(p.y.y, I32F32::one(), epsilon = I32F32::from_bits(4));
+    }
+}
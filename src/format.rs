@@ -0,0 +1,310 @@
+// `Display` and `FromStr` for vectors and matrices.
+//
+// Vectors print as `(1, 2, 3)`, matrices as one bracketed row per line with the columns
+// right-aligned. A precision like `{:.3}` is applied to every component.
+
+use crate::matrix::*;
+use crate::vector::*;
+use core::array;
+use core::fmt::{self, Display, Write};
+use core::str::FromStr;
+
+/// Error returned when parsing a vector or matrix fails. `row` is the zero-based line of a
+/// matrix and `None` for vectors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError<E> {
+    /// Unbalanced or mismatched brackets.
+    Delimiters { row: Option<usize> },
+    /// A matrix has the wrong number of rows.
+    Rows { expected: usize, found: usize },
+    /// A vector or matrix row has the wrong number of components.
+    Length {
+        row: Option<usize>,
+        expected: usize,
+        found: usize,
+    },
+    /// A component could not be parsed.
+    Component {
+        row: Option<usize>,
+        index: usize,
+        error: E,
+    },
+}
+
+impl<E: Display> Display for ParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let row = match self {
+            ParseError::Delimiters { row }
+            | ParseError::Length { row, .. }
+            | ParseError::Component { row, .. } => *row,
+            ParseError::Rows { .. } => None,
+        };
+        if let Some(row) = row {
+            write!(f, "row {}: ", row)?;
+        }
+        match self {
+            ParseError::Delimiters { .. } => write!(f, "unbalanced or mismatched brackets"),
+            ParseError::Rows { expected, found } => {
+                write!(f, "expected {} rows, found {}", expected, found)
+            }
+            ParseError::Length {
+                expected, found, ..
+            } => write!(f, "expected {} values, found {}", expected, found),
+            ParseError::Component { index, error, .. } => {
+                write!(f, "invalid value at index {}: {}", index, error)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for ParseError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Component { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+// Counts the characters a value occupies when formatted.
+struct Counter(usize);
+
+impl Write for Counter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+fn write_component<W: Write, T: Display>(
+    w: &mut W,
+    v: &T,
+    precision: Option<usize>,
+) -> fmt::Result {
+    match precision {
+        Some(p) => write!(w, "{:.*}", p, v),
+        None => write!(w, "{}", v),
+    }
+}
+
+fn component_width<T: Display>(v: &T, precision: Option<usize>) -> usize {
+    let mut c = Counter(0);
+    // Counting cannot fail unless the `Display` impl of `T` does.
+    let _ = write_component(&mut c, v, precision);
+    c.0
+}
+
+// Parses `(a, b, c)`, `[a b c]` or `a b c`.
+fn parse_components<T: FromStr, const N: usize>(
+    s: &str,
+    row: Option<usize>,
+) -> Result<[T; N], ParseError<T::Err>> {
+    let s = s.trim();
+    let inner = match (s.chars().next(), s.chars().last()) {
+        (Some('('), Some(')')) | (Some('['), Some(']')) if s.len() >= 2 => &s[1..s.len() - 1],
+        _ => s,
+    };
+    if inner.contains(['(', ')', '[', ']']) {
+        return Err(ParseError::Delimiters { row });
+    }
+
+    let mut out: [Option<T>; N] = array::from_fn(|_| None);
+    let mut found = 0;
+    let mut push = |token: &str| {
+        if found < N {
+            match token.parse() {
+                Ok(v) => out[found] = Some(v),
+                Err(error) => {
+                    return Err(ParseError::Component {
+                        row,
+                        index: found,
+                        error,
+                    })
+                }
+            }
+        }
+        found += 1;
+        Ok(())
+    };
+    if inner.contains(',') {
+        inner.split(',').try_for_each(|t| push(t.trim()))?;
+    } else {
+        inner.split_whitespace().try_for_each(&mut push)?;
+    }
+    if found != N {
+        return Err(ParseError::Length {
+            row,
+            expected: N,
+            found,
+        });
+    }
+    Ok(out.map(|v| v.unwrap()))
+}
+
+macro_rules! impl_format_vector {
+    ($VectorN: ident, $n: expr, $($field: ident),+) => {
+        impl<T> $VectorN<T> {
+            #[inline]
+            fn components(&self) -> [&T; $n] {
+                [$(&self.$field),+]
+            }
+
+            #[inline]
+            fn from_components(a: [T; $n]) -> $VectorN<T> {
+                let [$($field),+] = a;
+                $VectorN::new($($field),+)
+            }
+        }
+
+        impl<T: Display> Display for $VectorN<T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_char('(')?;
+                for (i, v) in self.components().iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write_component(f, v, f.precision())?;
+                }
+                f.write_char(')')
+            }
+        }
+
+        impl<T: FromStr> FromStr for $VectorN<T> {
+            type Err = ParseError<T::Err>;
+
+            fn from_str(s: &str) -> Result<$VectorN<T>, Self::Err> {
+                parse_components(s, None).map($VectorN::from_components)
+            }
+        }
+    };
+}
+
+macro_rules! impl_format_matrix {
+    ($MatrixN: ident, $VectorN: ident, $n: expr, $($field: ident),+) => {
+        impl<T: Display> Display for $MatrixN<T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let columns = [$(self.$field.components()),+];
+                let widths = columns.map(|c| {
+                    c.iter().map(|v| component_width(*v, f.precision())).max().unwrap_or(0)
+                });
+                for row in 0..$n {
+                    if row > 0 {
+                        f.write_char('\n')?;
+                    }
+                    f.write_char('[')?;
+                    for (i, (column, width)) in columns.iter().zip(&widths).enumerate() {
+                        if i > 0 {
+                            f.write_char(' ')?;
+                        }
+                        let v = column[row];
+                        for _ in component_width(v, f.precision())..*width {
+                            f.write_char(' ')?;
+                        }
+                        write_component(f, v, f.precision())?;
+                    }
+                    f.write_char(']')?;
+                }
+                Ok(())
+            }
+        }
+
+        /// Parses one row per non-empty line, in any of the formats accepted for vectors.
+        impl<T: FromStr + Copy> FromStr for $MatrixN<T> {
+            type Err = ParseError<T::Err>;
+
+            fn from_str(s: &str) -> Result<$MatrixN<T>, Self::Err> {
+                let mut rows = [[None; $n]; $n];
+                let mut found = 0;
+                for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                    if found < $n {
+                        let row: [T; $n] = parse_components(line, Some(found))?;
+                        rows[found] = row.map(Some);
+                    }
+                    found += 1;
+                }
+                if found != $n {
+                    return Err(ParseError::Rows {
+                        expected: $n,
+                        found,
+                    });
+                }
+                let [$($field),+] = array::from_fn(|c| {
+                    $VectorN::from_components(array::from_fn(|r| rows[r][c].unwrap()))
+                });
+                Ok($MatrixN::new($($field),+))
+            }
+        }
+    };
+}
+
+impl_format_vector!(Vector2, 2, x, y);
+impl_format_vector!(Vector3, 3, x, y, z);
+impl_format_vector!(Vector4, 4, x, y, z, w);
+
+impl_format_matrix!(Matrix2, Vector2, 2, x, y);
+impl_format_matrix!(Matrix3, Vector3, 3, x, y, z);
+impl_format_matrix!(Matrix4, Vector4, 4, x, y, z, w);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vector_display() {
+        let v = Vector3::new(1.0, -2.5, 3.0);
+        assert_eq!(format!("{}", v), "(1, -2.5, 3)");
+        assert_eq!(format!("{:.2}", v), "(1.00, -2.50, 3.00)");
+        assert_eq!(format!("{}", Vector2::new(1, 2)), "(1, 2)");
+    }
+
+    #[test]
+    fn matrix_display() {
+        let m = Matrix3::translate(&Vector2::new(10.0, -2.0));
+        assert_eq!(
+            format!("{:.1}", m),
+            "[1.0 0.0 10.0]\n[0.0 1.0 -2.0]\n[0.0 0.0  1.0]"
+        );
+    }
+
+    #[test]
+    fn vector_from_str() {
+        let v = Vector3::new(1, 2, 3);
+        assert_eq!("(1, 2, 3)".parse(), Ok(v));
+        assert_eq!("[1 2 3]".parse(), Ok(v));
+        assert_eq!(" 1 2\t3 ".parse(), Ok(v));
+        assert_eq!(format!("{}", v).parse(), Ok(v));
+        assert_eq!(
+            "(1, 2]".parse::<Vector3<i32>>(),
+            Err(ParseError::Delimiters { row: None })
+        );
+        assert_eq!(
+            "(1, 2)".parse::<Vector3<i32>>(),
+            Err(ParseError::Length {
+                row: None,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert!(matches!(
+            "(1, x, 3)".parse::<Vector3<i32>>(),
+            Err(ParseError::Component {
+                row: None,
+                index: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn matrix_from_str() {
+        let m = Matrix4::translate(&Vector3::new(1.5, -2.0, 3.25));
+        assert_eq!(format!("{:.3}", m).parse(), Ok(m));
+        let m: Matrix2<i32> = "[1 2]\n\n  (3, 4)\n".parse().unwrap();
+        assert_eq!(m, Matrix2::new(Vector2::new(1, 3), Vector2::new(2, 4)));
+        let e = "[1 2]\n[3]".parse::<Matrix2<i32>>().unwrap_err();
+        assert_eq!(format!("{}", e), "row 1: expected 2 values, found 1");
+        let e = "[1 2]".parse::<Matrix2<i32>>().unwrap_err();
+        assert_eq!(format!("{}", e), "expected 2 rows, found 1");
+    }
+}
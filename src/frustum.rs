@@ -0,0 +1,194 @@
+use crate::aabb::Aabb3;
+use crate::matrix::Matrix4;
+use crate::plane::Plane;
+use crate::scalar::Real;
+use crate::vector::*;
+
+/// Depth range of clip space after the perspective divide.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ClipDepth {
+    /// `-1..=1`, as produced by `Matrix4::perspective` and `Matrix4::ortho` (OpenGL).
+    NegativeOneToOne,
+    /// `0..=1` (Direct3D, Vulkan, Metal).
+    ZeroToOne,
+}
+
+/// A convex volume bounded by six planes with normals pointing inside, in the order left,
+/// right, bottom, top, near, far.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frustum<T> {
+    pub planes: [Plane<T>; 6],
+}
+
+impl<T> Frustum<T> {
+    #[inline]
+    pub fn new(planes: [Plane<T>; 6]) -> Frustum<T> {
+        Frustum { planes }
+    }
+}
+
+impl<T: Real> Frustum<T> {
+    /// Gribb and Hartmann's extraction of the planes of the volume `m` maps to clip space,
+    /// in the space `m` maps from: the view frustum for a projection, the world space
+    /// frustum for a view-projection.
+    pub fn from_matrix(m: &Matrix4<T>, depth: ClipDepth) -> Frustum<T> {
+        let rows = Matrix4::transpose(m);
+        let plane = |v: Vector4<T>| {
+            Plane::normalize(&Plane::new(Vector3::new(v.x, v.y, v.z), v.w))
+        };
+        let near = match depth {
+            ClipDepth::NegativeOneToOne => rows.w + rows.z,
+            ClipDepth::ZeroToOne => rows.z,
+        };
+        Frustum::new([
+            plane(rows.w + rows.x),
+            plane(rows.w - rows.x),
+            plane(rows.w + rows.y),
+            plane(rows.w - rows.y),
+            plane(near),
+            plane(rows.w - rows.z),
+        ])
+    }
+
+    /// Corners of the near face followed by the far face, each in the order left-bottom,
+    /// right-bottom, left-top, right-top.
+    pub fn corners(f: &Frustum<T>) -> [Vector3<T>; 8] {
+        let [left, right, bottom, top, near, far] = &f.planes;
+        let corner = |a: &Plane<T>, b: &Plane<T>, c: &Plane<T>| {
+            let bc = Vector3::cross(&b.normal, &c.normal);
+            let ca = Vector3::cross(&c.normal, &a.normal);
+            let ab = Vector3::cross(&a.normal, &b.normal);
+            let p = bc * a.d + ca * b.d + ab * c.d;
+            p * (-Vector3::dot(&a.normal, &bc)).recip()
+        };
+        [
+            corner(left, bottom, near),
+            corner(right, bottom, near),
+            corner(left, top, near),
+            corner(right, top, near),
+            corner(left, bottom, far),
+            corner(right, bottom, far),
+            corner(left, top, far),
+            corner(right, top, far),
+        ]
+    }
+
+    #[inline]
+    pub fn contains_point(f: &Frustum<T>, p: &Vector3<T>) -> bool {
+        f.planes
+            .iter()
+            .all(|pl| Plane::signed_distance(pl, p) >= T::zero())
+    }
+
+    /// Whether the sphere is at least partly inside. Conservative: spheres near an edge of
+    /// the frustum may be reported as intersecting while just outside.
+    #[inline]
+    pub fn intersects_sphere(f: &Frustum<T>, center: &Vector3<T>, radius: T) -> bool {
+        f.planes
+            .iter()
+            .all(|pl| Plane::signed_distance(pl, center) >= -radius)
+    }
+
+    #[inline]
+    pub fn contains_sphere(f: &Frustum<T>, center: &Vector3<T>, radius: T) -> bool {
+        f.planes
+            .iter()
+            .all(|pl| Plane::signed_distance(pl, center) >= radius)
+    }
+
+    /// Whether the box is at least partly inside, conservative like `intersects_sphere`.
+    #[inline]
+    pub fn intersects_aabb(f: &Frustum<T>, b: &Aabb3<T>) -> bool {
+        // The corner furthest along each normal must be inside.
+        f.planes.iter().all(|pl| {
+            let m = BVec3::new(
+                pl.normal.x >= T::zero(),
+                pl.normal.y >= T::zero(),
+                pl.normal.z >= T::zero(),
+            );
+            Plane::signed_distance(pl, &Vector3::select(&m, &b.max, &b.min)) >= T::zero()
+        })
+    }
+
+    #[inline]
+    pub fn contains_aabb(f: &Frustum<T>, b: &Aabb3<T>) -> bool {
+        // The corner furthest against each normal must be inside.
+        f.planes.iter().all(|pl| {
+            let m = BVec3::new(
+                pl.normal.x >= T::zero(),
+                pl.normal.y >= T::zero(),
+                pl.normal.z >= T::zero(),
+            );
+            Plane::signed_distance(pl, &Vector3::select(&m, &b.min, &b.max)) >= T::zero()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    // Maps the OpenGL depth range to zero-to-one.
+    fn zero_to_one() -> Matrix4<f64> {
+        Matrix4::new(
+            Vector4::new(1.0, 0.0, 0.0, 0.0),
+            Vector4::new(0.0, 1.0, 0.0, 0.0),
+            Vector4::new(0.0, 0.0, 0.5, 0.0),
+            Vector4::new(0.0, 0.0, 0.5, 1.0),
+        )
+    }
+
+    #[test]
+    fn frustum_corners() {
+        let f = Frustum::from_matrix(
+            &Matrix4::ortho(-1.0, 1.0, -2.0, 2.0, 1.0, 10.0),
+            ClipDepth::NegativeOneToOne,
+        );
+        let c = Frustum::corners(&f);
+        assert_relative_eq!(c[0], Vector3::new(-1.0, -2.0, -1.0), epsilon = 1e-12);
+        assert_relative_eq!(c[7], Vector3::new(1.0, 2.0, -10.0), epsilon = 1e-12);
+
+        // A 90 degree vertical field of view, twice as wide as high.
+        let p = Matrix4::perspective(FRAC_PI_2, 2.0, 1.0, 10.0);
+        for (m, depth) in [
+            (p, ClipDepth::NegativeOneToOne),
+            (zero_to_one() * p, ClipDepth::ZeroToOne),
+        ] {
+            let c = Frustum::corners(&Frustum::from_matrix(&m, depth));
+            assert_relative_eq!(c[1], Vector3::new(2.0, -1.0, -1.0), epsilon = 1e-12);
+            assert_relative_eq!(c[6], Vector3::new(-20.0, 10.0, -10.0), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn frustum_culling() {
+        let view = Matrix4::translate(&Vector3::new(0.0, 0.0, -5.0));
+        let m = Matrix4::perspective(FRAC_PI_2, 1.0, 1.0, 100.0) * view;
+        let f = Frustum::from_matrix(&m, ClipDepth::NegativeOneToOne);
+        // The camera sits at z = 5 looking down -z.
+        assert!(Frustum::contains_point(&f, &Vector3::new(0.0, 0.0, 0.0)));
+        assert!(!Frustum::contains_point(&f, &Vector3::new(0.0, 0.0, 4.5)));
+        assert!(!Frustum::contains_point(&f, &Vector3::new(6.0, 0.0, 0.0)));
+
+        let c = Vector3::new(5.5, 0.0, 0.0);
+        assert!(Frustum::intersects_sphere(&f, &c, 1.0));
+        assert!(!Frustum::contains_sphere(&f, &c, 1.0));
+        assert!(!Frustum::intersects_sphere(&f, &c, 0.1));
+        assert!(Frustum::contains_sphere(
+            &f,
+            &Vector3::new(0.0, 0.0, 0.0),
+            1.0
+        ));
+
+        let b = Aabb3::new(Vector3::new(-1.0, -1.0, -1.0), Vector3::new(1.0, 1.0, 1.0));
+        assert!(Frustum::contains_aabb(&f, &b));
+        assert!(Frustum::intersects_aabb(&f, &b));
+        let t = Vector3::new(5.0, 0.0, 0.0);
+        let edge = Aabb3::new(b.min + t, b.max + t);
+        assert!(Frustum::intersects_aabb(&f, &edge));
+        assert!(!Frustum::contains_aabb(&f, &edge));
+        let outside = Aabb3::new(b.min + t * 2.0, b.max + t * 2.0);
+        assert!(!Frustum::intersects_aabb(&f, &outside));
+    }
+}
@@ -0,0 +1,397 @@
+//! Polygons and point sets in the plane: area and winding, point-in-polygon tests, convex
+//! hulls, convex clipping and triangulation. Orientation decisions use the exact
+//! `predicates::orient2d`, so results are consistent for collinear and nearly collinear
+//! input.
+//!
+//! Polygons are slices of vertices in order, implicitly closed.
+
+use crate::predicates::orient2d;
+use crate::vector::*;
+use alloc::vec::Vec;
+
+/// Shoelace area, positive for counter-clockwise polygons. Self-intersecting polygons
+/// count the regions of each winding with their sign.
+pub fn signed_area(polygon: &[Vector2<f64>]) -> f64 {
+    let n = polygon.len();
+    let sum: f64 = (0..n)
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+            a.x * b.y - a.y * b.x
+        })
+        .sum();
+    sum * 0.5
+}
+
+/// Whether a simple polygon winds counter-clockwise, decided exactly at its lowest, then
+/// leftmost, vertex. Polygons with fewer than three vertices or with that vertex on a
+/// straight angle are neither, and return `false`.
+pub fn is_counter_clockwise(polygon: &[Vector2<f64>]) -> bool {
+    let n = polygon.len();
+    if n < 3 {
+        return false;
+    }
+    let lowest = (1..n).fold(0, |m, i| {
+        let (p, q) = (polygon[i], polygon[m]);
+        if (p.y, p.x) < (q.y, q.x) {
+            i
+        } else {
+            m
+        }
+    });
+    let prev = &polygon[(lowest + n - 1) % n];
+    let next = &polygon[(lowest + 1) % n];
+    orient2d(prev, &polygon[lowest], next) > 0.0
+}
+
+// Whether `p` is on the closed segment `a, b`.
+fn on_segment(a: &Vector2<f64>, b: &Vector2<f64>, p: &Vector2<f64>) -> bool {
+    orient2d(a, b, p) == 0.0
+        && p.x >= a.x.min(b.x)
+        && p.x <= a.x.max(b.x)
+        && p.y >= a.y.min(b.y)
+        && p.y <= a.y.max(b.y)
+}
+
+/// Whether `p` is inside the polygon or on its boundary, by the non-zero winding rule.
+pub fn contains(polygon: &[Vector2<f64>], p: &Vector2<f64>) -> bool {
+    let n = polygon.len();
+    let mut winding = 0;
+    for i in 0..n {
+        let (a, b) = (&polygon[i], &polygon[(i + 1) % n]);
+        if on_segment(a, b, p) {
+            return true;
+        }
+        if a.y <= p.y {
+            if b.y > p.y && orient2d(a, b, p) > 0.0 {
+                winding += 1;
+            }
+        } else if b.y <= p.y && orient2d(a, b, p) < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding != 0
+}
+
+/// Indices of the vertices of the convex hull in counter-clockwise order, starting from
+/// the lowest-leftmost point, by Andrew's monotone chain. Points on the hull's edges and
+/// duplicates are left out, as are points with infinite or NaN coordinates. Fewer than
+/// three indices are returned when all points are collinear.
+pub fn convex_hull(points: &[Vector2<f64>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len())
+        .filter(|&i| points[i].x.is_finite() && points[i].y.is_finite())
+        .collect();
+    // Adding zero maps -0 to +0, which `total_cmp` would otherwise order apart.
+    order.sort_by(|&i, &j| {
+        let (p, q) = (points[i], points[j]);
+        (p.x + 0.0)
+            .total_cmp(&(q.x + 0.0))
+            .then((p.y + 0.0).total_cmp(&(q.y + 0.0)))
+    });
+    order.dedup_by(|i, j| points[*i] == points[*j]);
+    if order.len() < 3 {
+        return order;
+    }
+    // The lower hull left to right, then the upper hull right to left.
+    let mut hull: Vec<usize> = Vec::with_capacity(order.len() + 1);
+    for pass in 0..2 {
+        let start = hull.len();
+        for k in 0..order.len() {
+            let i = if pass == 0 {
+                order[k]
+            } else {
+                order[order.len() - 1 - k]
+            };
+            while hull.len() >= start + 2
+                && orient2d(
+                    &points[hull[hull.len() - 2]],
+                    &points[hull[hull.len() - 1]],
+                    &points[i],
+                ) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(i);
+        }
+        // The last point is the first of the next chain.
+        hull.pop();
+    }
+    if hull.len() < 3 {
+        hull.truncate(2);
+    }
+    // Start from the lowest-leftmost point.
+    let lowest = (0..hull.len()).fold(0, |m, k| {
+        let (p, q) = (points[hull[k]], points[hull[m]]);
+        if (p.y, p.x) < (q.y, q.x) {
+            k
+        } else {
+            m
+        }
+    });
+    hull.rotate_left(lowest);
+    hull
+}
+
+/// Sutherland–Hodgman clipping of `subject` against the convex, counter-clockwise
+/// polygon `clip`. The result holds new points where edges cross; it can have degenerate
+/// edges along the clip boundary when `subject` is concave.
+///
+/// Unlike `convex_hull` and `triangulate` this returns coordinates rather than indices: kept
+/// vertices of `subject` are copied, so the output cannot be mapped back to the inputs.
+pub fn clip(subject: &[Vector2<f64>], clip: &[Vector2<f64>]) -> Vec<Vector2<f64>> {
+    let mut output: Vec<Vector2<f64>> = subject.to_vec();
+    let mut input = Vec::with_capacity(subject.len());
+    for i in 0..clip.len() {
+        if output.is_empty() {
+            break;
+        }
+        let (a, b) = (&clip[i], &clip[(i + 1) % clip.len()]);
+        core::mem::swap(&mut input, &mut output);
+        output.clear();
+        let inside = |p: &Vector2<f64>| orient2d(a, b, p) >= 0.0;
+        let mut prev = input[input.len() - 1];
+        for &p in input.iter() {
+            match (inside(&prev), inside(&p)) {
+                (true, true) => output.push(p),
+                (true, false) => output.push(crossing(a, b, &prev, &p)),
+                (false, true) => {
+                    output.push(crossing(a, b, &prev, &p));
+                    output.push(p);
+                }
+                (false, false) => {}
+            }
+            prev = p;
+        }
+    }
+    output
+}
+
+// Where the segment `p, q` crosses the line through `a` and `b`.
+fn crossing(
+    a: &Vector2<f64>,
+    b: &Vector2<f64>,
+    p: &Vector2<f64>,
+    q: &Vector2<f64>,
+) -> Vector2<f64> {
+    let dp = orient2d(a, b, p);
+    let dq = orient2d(a, b, q);
+    *p + (*q - *p) * (dp / (dp - dq))
+}
+
+/// Ear clipping triangulation of a simple polygon of either winding, as index triples
+/// with the polygon's winding. Takes quadratic time for typical polygons. Collinear
+/// vertices are allowed; self-intersecting polygons give some set of triangles.
+pub fn triangulate(polygon: &[Vector2<f64>]) -> Vec<[usize; 3]> {
+    let n = polygon.len();
+    let mut triangles = Vec::with_capacity(n.saturating_sub(2));
+    if n < 3 {
+        return triangles;
+    }
+    // Orient so ears are the convex, counter-clockwise corners.
+    let ccw = signed_area(polygon) >= 0.0;
+    let mut remaining: Vec<usize> = if ccw {
+        (0..n).collect()
+    } else {
+        (0..n).rev().collect()
+    };
+    let corner = |remaining: &[usize], k: usize| {
+        let m = remaining.len();
+        [
+            remaining[(k + m - 1) % m],
+            remaining[k],
+            remaining[(k + 1) % m],
+        ]
+    };
+    let mut k = 0;
+    let mut stalled = 0;
+    while remaining.len() > 3 {
+        let [a, b, c] = corner(&remaining, k);
+        let (pa, pb, pc) = (&polygon[a], &polygon[b], &polygon[c]);
+        let convex = orient2d(pa, pb, pc) > 0.0;
+        // No other remaining vertex may lie in the closed ear, except copies of its corners.
+        let is_ear = convex
+            && remaining.iter().all(|&i| {
+                let p = &polygon[i];
+                i == a
+                    || i == b
+                    || i == c
+                    || p == pa
+                    || p == pb
+                    || p == pc
+                    || orient2d(pa, pb, p) < 0.0
+                    || orient2d(pb, pc, p) < 0.0
+                    || orient2d(pc, pa, p) < 0.0
+            });
+        // After a full pass without ears the input is degenerate or not simple: drop
+        // straight corners first, then clip anything to guarantee progress.
+        let forced = stalled >= remaining.len()
+            && (orient2d(pa, pb, pc) == 0.0 || stalled >= 2 * remaining.len());
+        if is_ear || forced {
+            if orient2d(pa, pb, pc) != 0.0 {
+                triangles.push(if ccw { [a, b, c] } else { [c, b, a] });
+            }
+            remaining.remove(k);
+            k %= remaining.len();
+            stalled = 0;
+        } else {
+            k = (k + 1) % remaining.len();
+            stalled += 1;
+        }
+    }
+    let [a, b, c] = [remaining[0], remaining[1], remaining[2]];
+    if orient2d(&polygon[a], &polygon[b], &polygon[c]) != 0.0 {
+        triangles.push(if ccw { [a, b, c] } else { [c, b, a] });
+    }
+    triangles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    fn v(x: f64, y: f64) -> Vector2<f64> {
+        Vector2::new(x, y)
+    }
+
+    // An L shape, counter-clockwise, with a collinear vertex on its bottom edge.
+    fn l_shape() -> [Vector2<f64>; 7] {
+        [
+            v(0.0, 0.0),
+            v(1.0, 0.0),
+            v(2.0, 0.0),
+            v(2.0, 1.0),
+            v(1.0, 1.0),
+            v(1.0, 2.0),
+            v(0.0, 2.0),
+        ]
+    }
+
+    #[test]
+    fn geometry2d_area_and_contains() {
+        let l = l_shape();
+        assert_eq!(signed_area(&l), 3.0);
+        assert!(is_counter_clockwise(&l));
+        let mut cw = l;
+        cw.reverse();
+        assert_eq!(signed_area(&cw), -3.0);
+        assert!(!is_counter_clockwise(&cw));
+
+        for polygon in [&l[..], &cw[..]] {
+            assert!(contains(polygon, &v(0.5, 1.5)));
+            assert!(contains(polygon, &v(1.5, 0.5)));
+            assert!(!contains(polygon, &v(1.5, 1.5)));
+            assert!(!contains(polygon, &v(-0.5, 0.5)));
+            // Boundary points, including vertices and a point level with a vertex.
+            assert!(contains(polygon, &v(1.0, 1.5)));
+            assert!(contains(polygon, &v(2.0, 0.0)));
+            assert!(contains(polygon, &v(0.0, 1.0)));
+            assert!(!contains(polygon, &v(2.5, 1.0)));
+        }
+    }
+
+    #[test]
+    fn geometry2d_convex_hull() {
+        let points = [
+            v(1.0, 1.0),
+            v(0.0, 0.0),
+            v(2.0, 0.0),
+            v(1.0, 0.0),
+            v(2.0, 2.0),
+            v(0.0, 2.0),
+            v(2.0, 2.0),
+            v(0.5, 1.5),
+            v(1.0, 2.0),
+        ];
+        assert_eq!(convex_hull(&points), vec![1, 2, 4, 5]);
+        assert_eq!(convex_hull(&points[..1]), vec![0]);
+        assert_eq!(
+            convex_hull(&[v(0.0, 0.0), v(2.0, 2.0), v(1.0, 1.0)]),
+            vec![0, 1]
+        );
+        let (nan, inf) = (f64::NAN, f64::INFINITY);
+        assert_eq!(
+            convex_hull(&[
+                v(nan, 0.0),
+                v(0.0, 0.0),
+                v(1.0, inf),
+                v(1.0, 0.0),
+                v(0.0, 1.0)
+            ]),
+            vec![1, 3, 4]
+        );
+        assert_eq!(convex_hull(&[v(-0.0, 0.0), v(0.0, 0.0)]), vec![0]);
+
+        let mut rng = SmallRng::seed_from_u64(7);
+        let points: Vec<_> = (0..200).map(|_| v(rng.gen(), rng.gen())).collect();
+        let hull = convex_hull(&points);
+        let polygon: Vec<_> = hull.iter().map(|&i| points[i]).collect();
+        assert!(is_counter_clockwise(&polygon));
+        for k in 0..hull.len() {
+            let (a, b) = (&polygon[k], &polygon[(k + 1) % hull.len()]);
+            assert!(points.iter().all(|p| orient2d(a, b, p) >= 0.0));
+        }
+    }
+
+    #[test]
+    fn geometry2d_clip() {
+        let square = [v(0.0, 0.0), v(2.0, 0.0), v(2.0, 2.0), v(0.0, 2.0)];
+        let diamond = [v(1.0, -0.5), v(2.5, 1.0), v(1.0, 2.5), v(-0.5, 1.0)];
+        let clipped = clip(&diamond, &square);
+        assert_eq!(clipped.len(), 8);
+        assert_relative_eq!(signed_area(&clipped), 4.0 - 4.0 * 0.125, epsilon = 1e-12);
+
+        let far = [v(5.0, 5.0), v(6.0, 5.0), v(6.0, 6.0)];
+        assert!(clip(&far, &square).is_empty());
+        let inner = [v(0.5, 0.5), v(1.5, 0.5), v(1.0, 1.5)];
+        assert_eq!(clip(&inner, &square), inner.to_vec());
+
+        // A concave subject keeps its area inside the clip region.
+        let clipped = clip(
+            &l_shape(),
+            &[v(0.5, -1.0), v(3.0, -1.0), v(3.0, 3.0), v(0.5, 3.0)],
+        );
+        assert_relative_eq!(signed_area(&clipped), 2.0, epsilon = 1e-12);
+    }
+
+    fn triangulated_area(polygon: &[Vector2<f64>], triangles: &[[usize; 3]]) -> f64 {
+        triangles
+            .iter()
+            .map(|t| signed_area(&[polygon[t[0]], polygon[t[1]], polygon[t[2]]]))
+            .sum()
+    }
+
+    #[test]
+    fn geometry2d_triangulate() {
+        let l = l_shape();
+        let triangles = triangulate(&l);
+        assert_eq!(triangles.len(), 5);
+        assert_eq!(triangulated_area(&l, &triangles), 3.0);
+        for t in &triangles {
+            assert!(orient2d(&l[t[0]], &l[t[1]], &l[t[2]]) > 0.0);
+        }
+
+        let mut cw = l;
+        cw.reverse();
+        let triangles = triangulate(&cw);
+        assert_eq!(triangulated_area(&cw, &triangles), -3.0);
+
+        // A comb, with many reflex vertices.
+        let mut comb = vec![v(0.0, 0.0), v(10.0, 0.0)];
+        for i in (0..5).rev() {
+            let x = i as f64 * 2.0;
+            comb.extend([v(x + 2.0, 3.0), v(x + 1.0, 1.0)]);
+        }
+        comb.push(v(0.0, 3.0));
+        let triangles = triangulate(&comb);
+        assert_eq!(triangles.len(), comb.len() - 2);
+        assert_relative_eq!(
+            triangulated_area(&comb, &triangles),
+            signed_area(&comb),
+            epsilon = 1e-12
+        );
+
+        assert!(triangulate(&l[..2]).is_empty());
+        assert!(triangulate(&[v(0.0, 0.0), v(1.0, 1.0), v(2.0, 2.0)]).is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+// Conversions from and to the types of other math crates, each behind a feature of the same
+// name. All matrices involved are column-major, so columns map to columns.
+
+#[cfg(feature = "mint")]
+mod mint_impl {
+    use crate::matrix::*;
+    use crate::vector::*;
+
+    macro_rules! impl_mint_vector {
+        ($VectorN: ident, $($field: ident),+) => {
+            impl<T> From<mint::$VectorN<T>> for $VectorN<T> {
+                #[inline]
+                fn from(v: mint::$VectorN<T>) -> $VectorN<T> {
+                    $VectorN::new($(v.$field),+)
+                }
+            }
+
+            impl<T> From<$VectorN<T>> for mint::$VectorN<T> {
+                #[inline]
+                fn from(v: $VectorN<T>) -> mint::$VectorN<T> {
+                    mint::$VectorN { $($field: v.$field),+ }
+                }
+            }
+
+            impl<T> mint::IntoMint for $VectorN<T> {
+                type MintType = mint::$VectorN<T>;
+            }
+        };
+    }
+
+    macro_rules! impl_mint_matrix {
+        ($MatrixN: ident, $ColumnMatrixN: ident, $($field: ident),+) => {
+            impl<T> From<mint::$ColumnMatrixN<T>> for $MatrixN<T> {
+                #[inline]
+                fn from(m: mint::$ColumnMatrixN<T>) -> $MatrixN<T> {
+                    $MatrixN::new($(m.$field.into()),+)
+                }
+            }
+
+            impl<T> From<$MatrixN<T>> for mint::$ColumnMatrixN<T> {
+                #[inline]
+                fn from(m: $MatrixN<T>) -> mint::$ColumnMatrixN<T> {
+                    mint::$ColumnMatrixN { $($field: m.$field.into()),+ }
+                }
+            }
+
+            impl<T> mint::IntoMint for $MatrixN<T> {
+                type MintType = mint::$ColumnMatrixN<T>;
+            }
+        };
+    }
+
+    impl_mint_vector!(Vector2, x, y);
+    impl_mint_vector!(Vector3, x, y, z);
+    impl_mint_vector!(Vector4, x, y, z, w);
+
+    impl_mint_matrix!(Matrix2, ColumnMatrix2, x, y);
+    impl_mint_matrix!(Matrix3, ColumnMatrix3, x, y, z);
+    impl_mint_matrix!(Matrix4, ColumnMatrix4, x, y, z, w);
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn mint_roundtrip() {
+            let v = Vector3::new(1, 2, 3);
+            let mv: mint::Vector3<i32> = v.into();
+            assert_eq!((mv.x, mv.y, mv.z), (1, 2, 3));
+            assert_eq!(Vector3::from(mv), v);
+
+            let m = Matrix2::new(Vector2::new(1, 2), Vector2::new(3, 4));
+            let mm: mint::ColumnMatrix2<i32> = m.into();
+            // The second column, not the second row.
+            assert_eq!((mm.y.x, mm.y.y), (3, 4));
+            assert_eq!(Matrix2::from(mm), m);
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+mod glam_impl {
+    use crate::matrix::*;
+    use crate::vector::*;
+
+    macro_rules! impl_glam_vector {
+        ($VectorN: ident<$T: ty>, $Glam: ident, $($field: ident),+) => {
+            impl From<glam::$Glam> for $VectorN<$T> {
+                #[inline]
+                fn from(v: glam::$Glam) -> $VectorN<$T> {
+                    $VectorN::new($(v.$field),+)
+                }
+            }
+
+            impl From<$VectorN<$T>> for glam::$Glam {
+                #[inline]
+                fn from(v: $VectorN<$T>) -> glam::$Glam {
+                    glam::$Glam::new($(v.$field),+)
+                }
+            }
+        };
+    }
+
+    macro_rules! impl_glam_matrix {
+        ($MatrixN: ident<$T: ty>, $Glam: ident, $($field: ident: $axis: ident),+) => {
+            impl From<glam::$Glam> for $MatrixN<$T> {
+                #[inline]
+                fn from(m: glam::$Glam) -> $MatrixN<$T> {
+                    $MatrixN::new($(m.$axis.into()),+)
+                }
+            }
+
+            impl From<$MatrixN<$T>> for glam::$Glam {
+                #[inline]
+                fn from(m: $MatrixN<$T>) -> glam::$Glam {
+                    glam::$Glam::from_cols($(m.$field.into()),+)
+                }
+            }
+        };
+    }
+
+    impl_glam_vector!(Vector2<f32>, Vec2, x, y);
+    impl_glam_vector!(Vector3<f32>, Vec3, x, y, z);
+    impl_glam_vector!(Vector4<f32>, Vec4, x, y, z, w);
+    impl_glam_vector!(Vector2<f64>, DVec2, x, y);
+    impl_glam_vector!(Vector3<f64>, DVec3, x, y, z);
+    impl_glam_vector!(Vector4<f64>, DVec4, x, y, z, w);
+    impl_glam_vector!(Vector2<i32>, IVec2, x, y);
+    impl_glam_vector!(Vector3<i32>, IVec3, x, y, z);
+    impl_glam_vector!(Vector4<i32>, IVec4, x, y, z, w);
+    impl_glam_vector!(Vector2<u32>, UVec2, x, y);
+    impl_glam_vector!(Vector3<u32>, UVec3, x, y, z);
+    impl_glam_vector!(Vector4<u32>, UVec4, x, y, z, w);
+
+    impl_glam_matrix!(Matrix2<f32>, Mat2, x: x_axis, y: y_axis);
+    impl_glam_matrix!(Matrix3<f32>, Mat3, x: x_axis, y: y_axis, z: z_axis);
+    impl_glam_matrix!(Matrix4<f32>, Mat4, x: x_axis, y: y_axis, z: z_axis, w: w_axis);
+    impl_glam_matrix!(Matrix2<f64>, DMat2, x: x_axis, y: y_axis);
+    impl_glam_matrix!(Matrix3<f64>, DMat3, x: x_axis, y: y_axis, z: z_axis);
+    impl_glam_matrix!(Matrix4<f64>, DMat4, x: x_axis, y: y_axis, z: z_axis, w: w_axis);
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn glam_roundtrip() {
+            let m = Matrix4::translate(&Vector3::new(1.0f32, 2.0, 3.0));
+            let g: glam::Mat4 = m.into();
+            assert_eq!(g.w_axis, glam::Vec4::new(1.0, 2.0, 3.0, 1.0));
+            assert_eq!(
+                g.transform_point3(glam::Vec3::ZERO),
+                glam::Vec3::new(1.0, 2.0, 3.0)
+            );
+            assert_eq!(Matrix4::from(g), m);
+            assert_eq!(Vector2::from(glam::IVec2::new(1, 2)), Vector2::new(1, 2));
+        }
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impl {
+    use crate::matrix::*;
+    use crate::vector::*;
+    use nalgebra::Scalar;
+
+    macro_rules! impl_nalgebra_vector {
+        ($VectorN: ident, $($field: ident),+) => {
+            impl<T: Scalar> From<nalgebra::$VectorN<T>> for $VectorN<T> {
+                #[inline]
+                fn from(v: nalgebra::$VectorN<T>) -> $VectorN<T> {
+                    $VectorN::new($(v.$field.clone()),+)
+                }
+            }
+
+            impl<T: Scalar> From<$VectorN<T>> for nalgebra::$VectorN<T> {
+                #[inline]
+                fn from(v: $VectorN<T>) -> nalgebra::$VectorN<T> {
+                    nalgebra::$VectorN::new($(v.$field),+)
+                }
+            }
+        };
+    }
+
+    macro_rules! impl_nalgebra_matrix {
+        ($MatrixN: ident, $VectorN: ident, $($field: ident: $i: expr),+) => {
+            impl<T: Scalar> From<nalgebra::$MatrixN<T>> for $MatrixN<T> {
+                #[inline]
+                fn from(m: nalgebra::$MatrixN<T>) -> $MatrixN<T> {
+                    $MatrixN::new($($VectorN::from(m.column($i).into_owned())),+)
+                }
+            }
+
+            impl<T: Scalar> From<$MatrixN<T>> for nalgebra::$MatrixN<T> {
+                #[inline]
+                fn from(m: $MatrixN<T>) -> nalgebra::$MatrixN<T> {
+                    nalgebra::$MatrixN::from_columns(&[$(m.$field.into()),+])
+                }
+            }
+        };
+    }
+
+    impl_nalgebra_vector!(Vector2, x, y);
+    impl_nalgebra_vector!(Vector3, x, y, z);
+    impl_nalgebra_vector!(Vector4, x, y, z, w);
+
+    impl_nalgebra_matrix!(Matrix2, Vector2, x: 0, y: 1);
+    impl_nalgebra_matrix!(Matrix3, Vector3, x: 0, y: 1, z: 2);
+    impl_nalgebra_matrix!(Matrix4, Vector4, x: 0, y: 1, z: 2, w: 3);
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn nalgebra_roundtrip() {
+            let m = Matrix4::translate(&Vector3::new(1.0, 2.0, 3.0));
+            let n: nalgebra::Matrix4<f64> = m.into();
+            // Row 0, column 3.
+            assert_eq!(n[(0, 3)], 1.0);
+            let p = n.transform_point(&nalgebra::Point3::new(0.0, 0.0, 0.0));
+            assert_eq!(p, nalgebra::Point3::new(1.0, 2.0, 3.0));
+            assert_eq!(Matrix4::from(n), m);
+        }
+    }
+}
+
+#[cfg(feature = "cgmath")]
+mod cgmath_impl {
+    use crate::matrix::*;
+    use crate::vector::*;
+
+    macro_rules! impl_cgmath {
+        ($Type: ident, $($field: ident),+) => {
+            impl<T> From<cgmath::$Type<T>> for $Type<T> {
+                #[inline]
+                fn from(v: cgmath::$Type<T>) -> $Type<T> {
+                    $Type::new($(v.$field.into()),+)
+                }
+            }
+
+            impl<T> From<$Type<T>> for cgmath::$Type<T> {
+                #[inline]
+                fn from(v: $Type<T>) -> cgmath::$Type<T> {
+                    cgmath::$Type { $($field: v.$field.into()),+ }
+                }
+            }
+        };
+    }
+
+    impl_cgmath!(Vector2, x, y);
+    impl_cgmath!(Vector3, x, y, z);
+    impl_cgmath!(Vector4, x, y, z, w);
+    impl_cgmath!(Matrix2, x, y);
+    impl_cgmath!(Matrix3, x, y, z);
+    impl_cgmath!(Matrix4, x, y, z, w);
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use cgmath::Transform;
+
+        #[test]
+        fn cgmath_roundtrip() {
+            let m = Matrix4::translate(&Vector3::new(1.0, 2.0, 3.0));
+            let c: cgmath::Matrix4<f64> = m.into();
+            assert_eq!(c.w, cgmath::Vector4::new(1.0, 2.0, 3.0, 1.0));
+            let p = c.transform_point(cgmath::Point3::new(0.0, 0.0, 0.0));
+            assert_eq!(p, cgmath::Point3::new(1.0, 2.0, 3.0));
+            assert_eq!(Matrix4::from(c), m);
+        }
+    }
+}
@@ -0,0 +1,502 @@
+use crate::aabb::*;
+use crate::matrix::*;
+use crate::scalar::{Real, Scalar, SignedScalar};
+use crate::vector::*;
+use num_traits::{One, Zero};
+use core::cmp::Ordering;
+use core::f64::consts::{FRAC_PI_2, PI};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Floating point type usable as an `Interval` endpoint.
+pub trait IntervalBound: Real {
+    const INFINITY: Self;
+    const NEG_INFINITY: Self;
+
+    fn next_up(self) -> Self;
+
+    fn next_down(self) -> Self;
+
+    /// Rounds an `f64` outwards to the enclosing pair of representable values.
+    fn enclose_f64(v: f64) -> (Self, Self);
+
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_interval_bound {
+    ($($T: ident),+) => {
+        $(impl IntervalBound for $T {
+            const INFINITY: Self = $T::INFINITY;
+            const NEG_INFINITY: Self = $T::NEG_INFINITY;
+
+            #[inline]
+            fn next_up(self) -> Self {
+                $T::next_up(self)
+            }
+
+            #[inline]
+            fn next_down(self) -> Self {
+                $T::next_down(self)
+            }
+
+            #[inline]
+            fn enclose_f64(v: f64) -> (Self, Self) {
+                let r = v as $T;
+                if (r as f64) < v {
+                    (r, r.next_up())
+                } else if (r as f64) > v {
+                    (r.next_down(), r)
+                } else {
+                    (r, r)
+                }
+            }
+
+            #[inline]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })+
+    };
+}
+
+impl_interval_bound!(f32, f64);
+
+/// Endpoint product with `0 · ∞ = 0`, since a zero endpoint is an exact zero while an
+/// infinite one only stands for unbounded finite values.
+#[inline]
+fn mul_bound<T: IntervalBound>(a: T, b: T) -> T {
+    if a == T::zero() || b == T::zero() {
+        T::zero()
+    } else {
+        a * b
+    }
+}
+
+#[inline]
+fn min4<T: IntervalBound>(a: T, b: T, c: T, d: T) -> T {
+    let ab = if a < b { a } else { b };
+    let cd = if c < d { c } else { d };
+    if ab < cd {
+        ab
+    } else {
+        cd
+    }
+}
+
+#[inline]
+fn max4<T: IntervalBound>(a: T, b: T, c: T, d: T) -> T {
+    let ab = if a > b { a } else { b };
+    let cd = if c > d { c } else { d };
+    if ab > cd {
+        ab
+    } else {
+        cd
+    }
+}
+
+/// Closed interval `[lo, hi]` whose arithmetic rounds outwards, so the exact result of
+/// every operation on contained reals is contained in the resulting interval.
+///
+/// Trigonometric functions are widened by two ulps since the platform implementations
+/// are not correctly rounded.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Interval<T> {
+    pub lo: T,
+    pub hi: T,
+}
+
+impl<T: IntervalBound> Interval<T> {
+    #[inline]
+    pub fn new(lo: T, hi: T) -> Interval<T> {
+        Interval { lo, hi }
+    }
+
+    #[inline]
+    pub fn point(v: T) -> Interval<T> {
+        Interval::new(v, v)
+    }
+
+    #[inline]
+    pub fn entire() -> Interval<T> {
+        Interval::new(T::NEG_INFINITY, T::INFINITY)
+    }
+
+    #[inline]
+    pub fn width(i: &Interval<T>) -> T {
+        (i.hi - i.lo).next_up()
+    }
+
+    #[inline]
+    pub fn contains(i: &Interval<T>, v: T) -> bool {
+        i.lo <= v && v <= i.hi
+    }
+
+    #[inline]
+    fn widen(lo: T, hi: T, ulps: usize) -> Interval<T> {
+        let mut i = Interval::new(lo, hi);
+        for _ in 0..ulps {
+            i = Interval::new(i.lo.next_down(), i.hi.next_up());
+        }
+        i
+    }
+
+    // Whether some `phase + 2kπ` may lie in `[lo, hi]`, erring on the side of yes.
+    fn may_contain_period_point(lo: f64, hi: f64, phase: f64) -> bool {
+        let tau = 2.0 * PI;
+        let slack = (lo.abs() + hi.abs() + tau) * 8.0 * f64::EPSILON;
+        let k = ((lo - phase) / tau) as i64;
+        (k - 1..=k + 1).any(|k| {
+            let p = phase + k as f64 * tau;
+            p >= lo - slack && p <= hi + slack
+        })
+    }
+
+    fn bounds_f64(&self) -> Option<(f64, f64)> {
+        let (lo, hi) = (self.lo.to_f64(), self.hi.to_f64());
+        let huge = |v: f64| v.is_nan() || v.abs() > 1e15;
+        if huge(lo) || huge(hi) || hi - lo >= 2.0 * PI {
+            None
+        } else {
+            Some((lo, hi))
+        }
+    }
+
+    fn periodic(&self, value: fn(T) -> T, max_phase: f64, min_phase: f64) -> Interval<T> {
+        let one = T::one();
+        let (lo, hi) = match self.bounds_f64() {
+            Some(b) => b,
+            None => return Interval::new(-one, one),
+        };
+        let (a, b) = (value(self.lo), value(self.hi));
+        let mut r = Interval::widen(if a < b { a } else { b }, if a > b { a } else { b }, 2);
+        if Interval::<T>::may_contain_period_point(lo, hi, max_phase) {
+            r.hi = one;
+        }
+        if Interval::<T>::may_contain_period_point(lo, hi, min_phase) {
+            r.lo = -one;
+        }
+        if r.lo < -one {
+            r.lo = -one;
+        }
+        if r.hi > one {
+            r.hi = one;
+        }
+        r
+    }
+
+    /// Encloses the box `b` transformed by the affine `m` using interval arithmetic, which
+    /// accounts for rounding errors of the floating point transform. The bottom row of `m`
+    /// is ignored, so projective matrices need their own perspective division.
+    pub fn transform_box(m: &Matrix4<T>, b: &Aabb3<T>) -> Aabb3<T> {
+        let c = |v: &Vector4<T>| {
+            Vector4::new(
+                Interval::point(v.x),
+                Interval::point(v.y),
+                Interval::point(v.z),
+                Interval::point(v.w),
+            )
+        };
+        let im = Matrix4::new(c(&m.x), c(&m.y), c(&m.z), c(&m.w));
+        let p = Vector3::new(
+            Interval::new(b.min.x, b.max.x),
+            Interval::new(b.min.y, b.max.y),
+            Interval::new(b.min.z, b.max.z),
+        );
+        let r = Matrix4::transform_point(&im, &p);
+        Aabb3::new(
+            Vector3::new(r.x.lo, r.y.lo, r.z.lo),
+            Vector3::new(r.x.hi, r.y.hi, r.z.hi),
+        )
+    }
+}
+
+impl<T: IntervalBound> Add<Interval<T>> for Interval<T> {
+    type Output = Interval<T>;
+
+    #[inline]
+    fn add(self, rhs: Interval<T>) -> Self::Output {
+        Interval::new((self.lo + rhs.lo).next_down(), (self.hi + rhs.hi).next_up())
+    }
+}
+
+impl<T: IntervalBound> Sub<Interval<T>> for Interval<T> {
+    type Output = Interval<T>;
+
+    #[inline]
+    fn sub(self, rhs: Interval<T>) -> Self::Output {
+        Interval::new((self.lo - rhs.hi).next_down(), (self.hi - rhs.lo).next_up())
+    }
+}
+
+impl<T: IntervalBound> Mul<Interval<T>> for Interval<T> {
+    type Output = Interval<T>;
+
+    #[inline]
+    fn mul(self, rhs: Interval<T>) -> Self::Output {
+        let (a, b, c, d) = (
+            mul_bound(self.lo, rhs.lo),
+            mul_bound(self.lo, rhs.hi),
+            mul_bound(self.hi, rhs.lo),
+            mul_bound(self.hi, rhs.hi),
+        );
+        Interval::new(min4(a, b, c, d).next_down(), max4(a, b, c, d).next_up())
+    }
+}
+
+impl<T: IntervalBound> Div<Interval<T>> for Interval<T> {
+    type Output = Interval<T>;
+
+    #[inline]
+    fn div(self, rhs: Interval<T>) -> Self::Output {
+        if rhs.lo <= T::zero() && rhs.hi >= T::zero() {
+            return Interval::entire();
+        }
+        let (a, b, c, d) = (
+            self.lo / rhs.lo,
+            self.lo / rhs.hi,
+            self.hi / rhs.lo,
+            self.hi / rhs.hi,
+        );
+        Interval::new(min4(a, b, c, d).next_down(), max4(a, b, c, d).next_up())
+    }
+}
+
+impl<T: IntervalBound> Neg for Interval<T> {
+    type Output = Interval<T>;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Interval::new(-self.hi, -self.lo)
+    }
+}
+
+impl<T: IntervalBound> AddAssign<Interval<T>> for Interval<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Interval<T>) {
+        *self = *self + rhs
+    }
+}
+
+impl<T: IntervalBound> SubAssign<Interval<T>> for Interval<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Interval<T>) {
+        *self = *self - rhs
+    }
+}
+
+impl<T: IntervalBound> MulAssign<Interval<T>> for Interval<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Interval<T>) {
+        *self = *self * rhs
+    }
+}
+
+impl<T: IntervalBound> DivAssign<Interval<T>> for Interval<T> {
+    #[inline]
+    fn div_assign(&mut self, rhs: Interval<T>) {
+        *self = *self / rhs
+    }
+}
+
+/// Intervals are ordered only when they do not overlap.
+impl<T: IntervalBound> PartialOrd for Interval<T> {
+    fn partial_cmp(&self, other: &Interval<T>) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.hi < other.lo {
+            Some(Ordering::Less)
+        } else if self.lo > other.hi {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: IntervalBound> Zero for Interval<T> {
+    #[inline]
+    fn zero() -> Interval<T> {
+        Interval::point(T::zero())
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.lo.is_zero() && self.hi.is_zero()
+    }
+}
+
+impl<T: IntervalBound> One for Interval<T> {
+    #[inline]
+    fn one() -> Interval<T> {
+        Interval::point(T::one())
+    }
+}
+
+impl<T: IntervalBound> Scalar for Interval<T> {}
+
+impl<T: IntervalBound> SignedScalar for Interval<T> {
+    #[inline]
+    fn abs(self) -> Self {
+        if self.lo >= T::zero() {
+            self
+        } else if self.hi <= T::zero() {
+            -self
+        } else {
+            let m = if -self.lo > self.hi {
+                -self.lo
+            } else {
+                self.hi
+            };
+            Interval::new(T::zero(), m)
+        }
+    }
+}
+
+impl<T: IntervalBound> Real for Interval<T> {
+    #[inline]
+    fn from_f64(v: f64) -> Self {
+        let (lo, hi) = T::enclose_f64(v);
+        Interval::new(lo, hi)
+    }
+
+    /// Encloses the square roots of the non-negative part of the interval; an entirely
+    /// negative interval yields `[0, 0]`.
+    fn sqrt(self) -> Self {
+        if self.hi < T::zero() {
+            return Interval::point(T::zero());
+        }
+        let lo = if self.lo > T::zero() {
+            self.lo.sqrt().next_down()
+        } else {
+            T::zero()
+        };
+        let lo = if lo < T::zero() { T::zero() } else { lo };
+        Interval::new(lo, self.hi.sqrt().next_up())
+    }
+
+    fn sin_cos(self) -> (Self, Self) {
+        let sin = self.periodic(|x| x.sin_cos().0, FRAC_PI_2, -FRAC_PI_2);
+        let cos = self.periodic(|x| x.sin_cos().1, 0.0, PI);
+        (sin, cos)
+    }
+
+    fn tan(self) -> Self {
+        let (lo, hi) = match self.bounds_f64() {
+            Some(b) => b,
+            None => return Interval::entire(),
+        };
+        if Interval::<T>::may_contain_period_point(lo, hi, FRAC_PI_2)
+            || Interval::<T>::may_contain_period_point(lo, hi, -FRAC_PI_2)
+        {
+            return Interval::entire();
+        }
+        Interval::widen(self.lo.tan(), self.hi.tan(), 2)
+    }
+
+    #[inline]
+    fn recip(self) -> Self {
+        Interval::one() / self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn i(lo: f64, hi: f64) -> Interval<f64> {
+        Interval::new(lo, hi)
+    }
+
+    #[test]
+    fn interval_add_rounds_outwards() {
+        let s = Interval::point(0.1) + Interval::point(0.2);
+        assert!(s.lo < s.hi);
+        assert!(Interval::contains(&s, 0.1 + 0.2));
+    }
+
+    #[test]
+    fn interval_from_f64_f32() {
+        let v = <Interval<f32> as Real>::from_f64(0.1);
+        assert!((v.lo as f64) < 0.1 && 0.1 < (v.hi as f64));
+        assert_eq!(
+            <Interval<f32> as Real>::from_f64(0.5),
+            Interval::point(0.5f32)
+        );
+    }
+
+    #[test]
+    fn interval_mul_div() {
+        let p = i(-2.0, 3.0) * i(4.0, 5.0);
+        assert!(p.lo <= -10.0 && p.hi >= 15.0);
+        assert!(Interval::width(&p) < 25.0 + 1e-12);
+        let q = i(1.0, 2.0) / i(-1.0, 1.0);
+        assert_eq!(q, Interval::entire());
+        let r = i(1.0, 2.0) / i(4.0, 8.0);
+        assert!(r.lo <= 0.125 && r.hi >= 0.5);
+        let z = Interval::entire() * i(0.0, 0.0);
+        assert!(Interval::contains(&z, 0.0) && z.hi < 1e-300);
+        let h = Interval::entire() * i(0.0, 1.0);
+        assert_eq!(h, Interval::entire());
+    }
+
+    #[test]
+    fn interval_abs_sqrt() {
+        assert_eq!(i(-3.0, 2.0).abs(), i(0.0, 3.0));
+        let s = i(-1.0, 4.0).sqrt();
+        assert_eq!(s.lo, 0.0);
+        assert!(s.hi >= 2.0);
+        assert_eq!(i(-4.0, -1.0).sqrt(), i(0.0, 0.0));
+    }
+
+    #[test]
+    fn interval_sin_cos() {
+        let (s, c) = i(0.0, PI).sin_cos();
+        assert_eq!(s.hi, 1.0);
+        assert!(s.lo <= 0.0);
+        assert_eq!(c.lo, -1.0);
+        assert_eq!(c.hi, 1.0);
+        let (s, c) = i(0.1, 0.2).sin_cos();
+        assert!(s.lo <= 0.1f64.sin() && 0.2f64.sin() <= s.hi);
+        assert!(c.lo <= 0.2f64.cos() && 0.1f64.cos() <= c.hi);
+        assert!(s.hi < 1.0 && c.lo > 0.9);
+    }
+
+    #[test]
+    fn interval_tan() {
+        let t = i(0.1, 0.2).tan();
+        assert!(t.lo <= 0.1f64.tan() && 0.2f64.tan() <= t.hi);
+        assert_eq!(i(1.5, 1.6).tan(), Interval::entire());
+    }
+
+    #[test]
+    fn interval_matrix4_rotate_encloses_float() {
+        let angle = 0.7;
+        let axis = Vector3::new(1.0, 2.0, 3.0);
+        let p = Vector3::new(0.3, -1.5, 2.0);
+        let f = Matrix4::transform_point(&Matrix4::rotate(angle, &axis), &p);
+        let pi = |v: f64| Interval::point(v);
+        let ir = Matrix4::rotate(pi(angle), &Vector3::new(pi(1.0), pi(2.0), pi(3.0)));
+        let ip = Matrix4::transform_point(&ir, &Vector3::new(pi(p.x), pi(p.y), pi(p.z)));
+        assert!(Interval::contains(&ip.x, f.x));
+        assert!(Interval::contains(&ip.y, f.y));
+        assert!(Interval::contains(&ip.z, f.z));
+        assert!(Interval::width(&ip.x) < 1e-12);
+    }
+
+    #[test]
+    fn interval_transform_box() {
+        let m = Matrix4::translate(&Vector3::new(1.0, -2.0, 0.5))
+            * Matrix4::rotate(0.3, &Vector3::new(0.0, 1.0, 1.0))
+            * Matrix4::scale(&Vector3::new(2.0, 1.0, 0.5));
+        let b = Aabb3::new(Vector3::new(-1.0, -2.0, -3.0), Vector3::new(1.0, 0.5, 3.0));
+        let r = Interval::transform_box(&m, &b);
+        for &x in &[b.min.x, b.max.x] {
+            for &y in &[b.min.y, b.max.y] {
+                for &z in &[b.min.z, b.max.z] {
+                    let p = Matrix4::transform_point(&m, &Vector3::new(x, y, z));
+                    assert!(BVec3::all(&Vector3::cmple(&r.min, &p)));
+                    assert!(BVec3::all(&Vector3::cmple(&p, &r.max)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,374 @@
+// GPU buffer layouts. The std140/std430 wrappers are meant for 4 byte scalars (`f32`, `i32`,
+// `u32`); they convert to and from the plain types with `From`.
+//
+// The wrappers always pad a `vec3` to 16 bytes, whereas GLSL packs a following scalar into
+// its last 4 bytes. Declare such scalars before the `vec3` or add them to the padding manually.
+
+use crate::matrix::*;
+use crate::vector::*;
+
+#[repr(C, align(8))]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Std140Vector2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Std140Vector3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    padding: T,
+}
+
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Std140Vector4<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    pub w: T,
+}
+
+/// Every column is padded to 16 bytes, like array elements in std140.
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Std140Matrix2<T> {
+    pub x: Std140Vector2<T>,
+    padding_x: [T; 2],
+    pub y: Std140Vector2<T>,
+    padding_y: [T; 2],
+}
+
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Std140Matrix3<T> {
+    pub x: Std140Vector3<T>,
+    pub y: Std140Vector3<T>,
+    pub z: Std140Vector3<T>,
+}
+
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Std140Matrix4<T> {
+    pub x: Std140Vector4<T>,
+    pub y: Std140Vector4<T>,
+    pub z: Std140Vector4<T>,
+    pub w: Std140Vector4<T>,
+}
+
+/// std430 stores `mat2` columns tightly packed, all other types share the std140 layout.
+#[repr(C, align(8))]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Std430Matrix2<T> {
+    pub x: Std140Vector2<T>,
+    pub y: Std140Vector2<T>,
+}
+
+pub type Std430Vector2<T> = Std140Vector2<T>;
+pub type Std430Vector3<T> = Std140Vector3<T>;
+pub type Std430Vector4<T> = Std140Vector4<T>;
+pub type Std430Matrix3<T> = Std140Matrix3<T>;
+pub type Std430Matrix4<T> = Std140Matrix4<T>;
+
+impl<T> From<Vector2<T>> for Std140Vector2<T> {
+    #[inline]
+    fn from(v: Vector2<T>) -> Std140Vector2<T> {
+        Std140Vector2 { x: v.x, y: v.y }
+    }
+}
+
+impl<T> From<Std140Vector2<T>> for Vector2<T> {
+    #[inline]
+    fn from(v: Std140Vector2<T>) -> Vector2<T> {
+        Vector2::new(v.x, v.y)
+    }
+}
+
+impl<T: Default> From<Vector3<T>> for Std140Vector3<T> {
+    #[inline]
+    fn from(v: Vector3<T>) -> Std140Vector3<T> {
+        Std140Vector3 {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+            padding: T::default(),
+        }
+    }
+}
+
+impl<T> From<Std140Vector3<T>> for Vector3<T> {
+    #[inline]
+    fn from(v: Std140Vector3<T>) -> Vector3<T> {
+        Vector3::new(v.x, v.y, v.z)
+    }
+}
+
+impl<T> From<Vector4<T>> for Std140Vector4<T> {
+    #[inline]
+    fn from(v: Vector4<T>) -> Std140Vector4<T> {
+        Std140Vector4 {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+            w: v.w,
+        }
+    }
+}
+
+impl<T> From<Std140Vector4<T>> for Vector4<T> {
+    #[inline]
+    fn from(v: Std140Vector4<T>) -> Vector4<T> {
+        Vector4::new(v.x, v.y, v.z, v.w)
+    }
+}
+
+impl<T: Default + Copy> From<Matrix2<T>> for Std140Matrix2<T> {
+    #[inline]
+    fn from(m: Matrix2<T>) -> Std140Matrix2<T> {
+        Std140Matrix2 {
+            x: m.x.into(),
+            padding_x: [T::default(); 2],
+            y: m.y.into(),
+            padding_y: [T::default(); 2],
+        }
+    }
+}
+
+impl<T> From<Std140Matrix2<T>> for Matrix2<T> {
+    #[inline]
+    fn from(m: Std140Matrix2<T>) -> Matrix2<T> {
+        Matrix2::new(m.x.into(), m.y.into())
+    }
+}
+
+impl<T> From<Matrix2<T>> for Std430Matrix2<T> {
+    #[inline]
+    fn from(m: Matrix2<T>) -> Std430Matrix2<T> {
+        Std430Matrix2 {
+            x: m.x.into(),
+            y: m.y.into(),
+        }
+    }
+}
+
+impl<T> From<Std430Matrix2<T>> for Matrix2<T> {
+    #[inline]
+    fn from(m: Std430Matrix2<T>) -> Matrix2<T> {
+        Matrix2::new(m.x.into(), m.y.into())
+    }
+}
+
+impl<T: Default> From<Matrix3<T>> for Std140Matrix3<T> {
+    #[inline]
+    fn from(m: Matrix3<T>) -> Std140Matrix3<T> {
+        Std140Matrix3 {
+            x: m.x.into(),
+            y: m.y.into(),
+            z: m.z.into(),
+        }
+    }
+}
+
+impl<T> From<Std140Matrix3<T>> for Matrix3<T> {
+    #[inline]
+    fn from(m: Std140Matrix3<T>) -> Matrix3<T> {
+        Matrix3::new(m.x.into(), m.y.into(), m.z.into())
+    }
+}
+
+impl<T> From<Matrix4<T>> for Std140Matrix4<T> {
+    #[inline]
+    fn from(m: Matrix4<T>) -> Std140Matrix4<T> {
+        Std140Matrix4 {
+            x: m.x.into(),
+            y: m.y.into(),
+            z: m.z.into(),
+            w: m.w.into(),
+        }
+    }
+}
+
+impl<T> From<Std140Matrix4<T>> for Matrix4<T> {
+    #[inline]
+    fn from(m: Std140Matrix4<T>) -> Matrix4<T> {
+        Matrix4::new(m.x.into(), m.y.into(), m.z.into(), m.w.into())
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+mod pod {
+    use super::*;
+    use crate::wide::*;
+    use bytemuck::{Pod, Zeroable};
+
+    // `#[repr(C)]` structs whose fields all have the same type contain no padding.
+    macro_rules! impl_pod {
+        ($($Type: ident),+) => {
+            $(
+                unsafe impl<T: Zeroable> Zeroable for $Type<T> {}
+                unsafe impl<T: Pod> Pod for $Type<T> {}
+            )+
+        };
+    }
+
+    impl_pod!(Vector2, Vector3, Vector4, Matrix2, Matrix3, Matrix4, Vector3x4, Vector3x8);
+
+    unsafe impl Zeroable for BVec2 {}
+    unsafe impl Zeroable for BVec3 {}
+    unsafe impl Zeroable for BVec4 {}
+
+    // The aligned wrappers have no implicit padding only if `T` is 4 bytes.
+    macro_rules! impl_pod_std140 {
+        ($($T: ty),+) => {
+            $(
+                unsafe impl Zeroable for Std140Vector2<$T> {}
+                unsafe impl Pod for Std140Vector2<$T> {}
+                unsafe impl Zeroable for Std140Vector3<$T> {}
+                unsafe impl Pod for Std140Vector3<$T> {}
+                unsafe impl Zeroable for Std140Vector4<$T> {}
+                unsafe impl Pod for Std140Vector4<$T> {}
+                unsafe impl Zeroable for Std140Matrix2<$T> {}
+                unsafe impl Pod for Std140Matrix2<$T> {}
+                unsafe impl Zeroable for Std140Matrix3<$T> {}
+                unsafe impl Pod for Std140Matrix3<$T> {}
+                unsafe impl Zeroable for Std140Matrix4<$T> {}
+                unsafe impl Pod for Std140Matrix4<$T> {}
+                unsafe impl Zeroable for Std430Matrix2<$T> {}
+                unsafe impl Pod for Std430Matrix2<$T> {}
+            )+
+        };
+    }
+
+    impl_pod_std140!(f32, i32, u32);
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn vector3_cast_slice() {
+            let v = [Vector3::new(1.0f32, 2.0, 3.0), Vector3::new(4.0, 5.0, 6.0)];
+            let f: &[f32] = bytemuck::cast_slice(&v);
+            assert_eq!(f, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+            let m: Matrix4<f32> = bytemuck::cast([0u32; 16]);
+            assert_eq!(m, Matrix4::identity() * 0.0);
+        }
+
+        #[test]
+        fn std140_matrix3_bytes() {
+            let m = Std140Matrix3::from(Matrix3::<f32>::identity());
+            let f: &[f32] = bytemuck::cast_slice(core::slice::from_ref(&m));
+            assert_eq!(
+                f,
+                &[1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::{align_of, offset_of, size_of};
+
+    #[test]
+    fn std140_vector_layout() {
+        assert_eq!(
+            (
+                size_of::<Std140Vector2<f32>>(),
+                align_of::<Std140Vector2<f32>>()
+            ),
+            (8, 8)
+        );
+        assert_eq!(
+            (
+                size_of::<Std140Vector3<f32>>(),
+                align_of::<Std140Vector3<f32>>()
+            ),
+            (16, 16)
+        );
+        assert_eq!(
+            (
+                size_of::<Std140Vector4<f32>>(),
+                align_of::<Std140Vector4<f32>>()
+            ),
+            (16, 16)
+        );
+        assert_eq!(offset_of!(Std140Vector3<f32>, z), 8);
+    }
+
+    #[test]
+    fn std140_matrix_layout() {
+        assert_eq!(
+            (
+                size_of::<Std140Matrix2<f32>>(),
+                align_of::<Std140Matrix2<f32>>()
+            ),
+            (32, 16)
+        );
+        assert_eq!(offset_of!(Std140Matrix2<f32>, y), 16);
+        assert_eq!(
+            (
+                size_of::<Std140Matrix3<f32>>(),
+                align_of::<Std140Matrix3<f32>>()
+            ),
+            (48, 16)
+        );
+        assert_eq!(offset_of!(Std140Matrix3<f32>, y), 16);
+        assert_eq!(offset_of!(Std140Matrix3<f32>, z), 32);
+        assert_eq!(
+            (
+                size_of::<Std140Matrix4<f32>>(),
+                align_of::<Std140Matrix4<f32>>()
+            ),
+            (64, 16)
+        );
+        assert_eq!(offset_of!(Std140Matrix4<f32>, w), 48);
+    }
+
+    #[test]
+    fn std430_matrix2_layout() {
+        assert_eq!(
+            (
+                size_of::<Std430Matrix2<f32>>(),
+                align_of::<Std430Matrix2<f32>>()
+            ),
+            (16, 8)
+        );
+        assert_eq!(offset_of!(Std430Matrix2<f32>, y), 8);
+    }
+
+    #[test]
+    fn std140_block_offsets() {
+        // layout(std140) uniform Block { vec2 a; vec3 b; mat3 c; vec4 d; };
+        #[repr(C)]
+        struct Block {
+            a: Std140Vector2<f32>,
+            b: Std140Vector3<f32>,
+            c: Std140Matrix3<f32>,
+            d: Std140Vector4<f32>,
+        }
+
+        assert_eq!(offset_of!(Block, a), 0);
+        assert_eq!(offset_of!(Block, b), 16);
+        assert_eq!(offset_of!(Block, c), 32);
+        assert_eq!(offset_of!(Block, d), 80);
+        assert_eq!(size_of::<Block>(), 96);
+    }
+
+    #[test]
+    fn std140_roundtrip() {
+        let m = Matrix3::new(
+            Vector3::new(1, 2, 3),
+            Vector3::new(4, 5, 6),
+            Vector3::new(7, 8, 9),
+        );
+        assert_eq!(Matrix3::from(Std140Matrix3::from(m)), m);
+        let m = Matrix2::new(Vector2::new(1, 2), Vector2::new(3, 4));
+        assert_eq!(Matrix2::from(Std140Matrix2::from(m)), m);
+        assert_eq!(Matrix2::from(Std430Matrix2::from(m)), m);
+    }
+}
@@ -1,12 +1,63 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("either the `std` or the `libm` feature must be enabled");
+
 #[cfg(test)]
 #[macro_use]
 extern crate approx;
 #[cfg(not(test))]
 extern crate approx;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(all(feature = "libm", not(feature = "std")))]
+extern crate libm;
 extern crate num_traits;
 
+pub use aabb::*;
+pub use dual::*;
+pub use fixed::*;
+pub use format::*;
+pub use frustum::*;
+pub use interval::*;
+pub use layout::*;
 pub use matrix::*;
+pub use obb::*;
+pub use plane::*;
+pub use ray::*;
+pub use scalar::*;
+pub use sphere::*;
+pub use triangle::*;
 pub use vector::*;
+pub use wide::*;
 
+mod aabb;
+mod batch;
+#[cfg(feature = "alloc")]
+pub mod bvh;
+pub mod collision;
+pub mod distance;
+mod dual;
+mod fixed;
+mod format;
+mod frustum;
+#[cfg(feature = "alloc")]
+pub mod geometry2d;
+mod interop;
+mod interval;
+mod layout;
 mod matrix;
+mod obb;
+mod plane;
+pub mod predicates;
+mod ray;
+pub mod sampling;
+mod scalar;
+pub mod sequence;
+#[cfg(feature = "serde")]
+pub mod serialization;
+mod simd;
+mod sphere;
+mod triangle;
 mod vector;
+mod wide;
@@ -1,23 +1,26 @@
+use crate::scalar::{Real, Scalar, SignedScalar};
 use crate::vector::*;
-use num_traits::{cast, Float};
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+use core::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
 #[cfg(test)]
 use std::f64::consts::FRAC_PI_2;
-use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
 
 macro_rules! generate_matrix_n {
     ($MatrixN: ident, $VectorN: ident, $($field: ident),+) => {
         #[repr(C)]
         #[derive(Copy, Clone, Debug, PartialEq)]
-        pub struct $MatrixN<T: Float> {
+        pub struct $MatrixN<T> {
             $(pub $field: $VectorN<T>),+
         }
 
-        impl<T: Float> $MatrixN<T> {
+        impl<T> $MatrixN<T> {
             #[inline]
             pub fn new($($field: $VectorN<T>),+) -> $MatrixN<T> {
                 $MatrixN { $($field: $field),+ }
             }
+        }
 
+        impl<T: Scalar> $MatrixN<T> {
             #[inline]
             fn add_matrix_and_matrix(a: &$MatrixN<T>, b: &$MatrixN<T>) -> $MatrixN<T> {
                 $MatrixN::new($(a.$field + b.$field),+)
@@ -28,18 +31,13 @@ macro_rules! generate_matrix_n {
                 $MatrixN::new($(a.$field - b.$field),+)
             }
 
-            fn mul_matrix_and_vector(a: &$MatrixN<T>, b: &$VectorN<T>) -> $VectorN<T> {
-                let t = $MatrixN::transpose(&a);
-                $VectorN::new($($VectorN::dot(&t.$field, &b)),+)
-            }
-
             #[inline]
             fn mul_matrix_and_scalar(a: &$MatrixN<T>, b: &T) -> $MatrixN<T> {
                 $MatrixN::new($(a.$field * *b),+)
             }
         }
 
-        impl<T: Float> Add<$MatrixN<T>> for $MatrixN<T> {
+        impl<T: Scalar> Add<$MatrixN<T>> for $MatrixN<T> {
             type Output = $MatrixN<T>;
 
             #[inline]
@@ -48,7 +46,7 @@ macro_rules! generate_matrix_n {
             }
         }
 
-        impl<T: Float> Sub<$MatrixN<T>> for $MatrixN<T> {
+        impl<T: Scalar> Sub<$MatrixN<T>> for $MatrixN<T> {
             type Output = $MatrixN<T>;
 
             #[inline]
@@ -57,7 +55,7 @@ macro_rules! generate_matrix_n {
             }
         }
 
-        impl<T: Float> Mul<$MatrixN<T>> for $MatrixN<T> {
+        impl<T: Scalar> Mul<$MatrixN<T>> for $MatrixN<T> {
             type Output = $MatrixN<T>;
 
             fn mul(self, rhs: $MatrixN<T>) -> Self::Output {
@@ -65,7 +63,7 @@ macro_rules! generate_matrix_n {
             }
         }
 
-        impl<T: Float> Mul<$VectorN<T>> for $MatrixN<T> {
+        impl<T: Scalar> Mul<$VectorN<T>> for $MatrixN<T> {
             type Output = $VectorN<T>;
 
             fn mul(self, rhs: $VectorN<T>) -> Self::Output {
@@ -73,7 +71,7 @@ macro_rules! generate_matrix_n {
             }
         }
 
-        impl<T: Float> Mul<T> for $MatrixN<T> {
+        impl<T: Scalar> Mul<T> for $MatrixN<T> {
             type Output = $MatrixN<T>;
 
             #[inline]
@@ -82,32 +80,76 @@ macro_rules! generate_matrix_n {
             }
         }
 
-        impl<T: Float> AddAssign<$MatrixN<T>> for $MatrixN<T> {
+        impl<T: Scalar> AddAssign<$MatrixN<T>> for $MatrixN<T> {
             #[inline]
             fn add_assign(&mut self, rhs: $MatrixN<T>) {
                 *self = $MatrixN::add_matrix_and_matrix(self, &rhs)
             }
         }
 
-        impl<T: Float> SubAssign<$MatrixN<T>> for $MatrixN<T> {
+        impl<T: Scalar> SubAssign<$MatrixN<T>> for $MatrixN<T> {
             #[inline]
             fn sub_assign(&mut self, rhs: $MatrixN<T>) {
                 *self = $MatrixN::sub_matrix_and_matrix(self, &rhs)
             }
         }
 
-        impl<T: Float> MulAssign<$MatrixN<T>> for $MatrixN<T> {
+        impl<T: Scalar> MulAssign<$MatrixN<T>> for $MatrixN<T> {
             fn mul_assign(&mut self, rhs: $MatrixN<T>) {
                 *self = $MatrixN::mul_matrix_and_matrix(self, &rhs)
             }
         }
 
-        impl<T: Float> MulAssign<T> for $MatrixN<T> {
+        impl<T: Scalar> MulAssign<T> for $MatrixN<T> {
             #[inline]
             fn mul_assign(&mut self, rhs: T) {
                 *self = $MatrixN::mul_matrix_and_scalar(self, &rhs)
             }
         }
+
+        impl<T: AbsDiffEq> AbsDiffEq for $MatrixN<T> where
+            T::Epsilon: Copy,
+        {
+            type Epsilon = T::Epsilon;
+
+            #[inline]
+            fn default_epsilon() -> T::Epsilon {
+                T::default_epsilon()
+            }
+
+            #[inline]
+            fn abs_diff_eq(&self, other: &Self, epsilon: T::Epsilon) -> bool {
+                $($VectorN::abs_diff_eq(&self.$field, &other.$field, epsilon))&&+
+            }
+        }
+
+        impl<T: RelativeEq> RelativeEq for $MatrixN<T> where
+            T::Epsilon: Copy,
+        {
+            #[inline]
+            fn default_max_relative() -> T::Epsilon {
+                T::default_max_relative()
+            }
+
+            #[inline]
+            fn relative_eq(&self, other: &Self, epsilon: T::Epsilon, max_relative: T::Epsilon) -> bool {
+                $($VectorN::relative_eq(&self.$field, &other.$field, epsilon, max_relative))&&+
+            }
+        }
+
+        impl<T: UlpsEq> UlpsEq for $MatrixN<T> where
+            T::Epsilon: Copy,
+        {
+            #[inline]
+            fn default_max_ulps() -> u32 {
+                T::default_max_ulps()
+            }
+
+            #[inline]
+            fn ulps_eq(&self, other: &Self, epsilon: T::Epsilon, max_ulps: u32) -> bool {
+                $($VectorN::ulps_eq(&self.$field, &other.$field, epsilon, max_ulps))&&+
+            }
+        }
     };
 }
 
@@ -115,7 +157,7 @@ generate_matrix_n!(Matrix2, Vector2, x, y);
 generate_matrix_n!(Matrix3, Vector3, x, y, z);
 generate_matrix_n!(Matrix4, Vector4, x, y, z, w);
 
-impl<T: Float> Matrix2<T> {
+impl<T: Scalar> Matrix2<T> {
     fn mul_matrix_and_matrix(a: &Matrix2<T>, b: &Matrix2<T>) -> Matrix2<T> {
         let t = Matrix2::transpose(a);
         Matrix2::new(
@@ -124,6 +166,11 @@ impl<T: Float> Matrix2<T> {
         )
     }
 
+    fn mul_matrix_and_vector(a: &Matrix2<T>, b: &Vector2<T>) -> Vector2<T> {
+        let t = Matrix2::transpose(a);
+        Vector2::new(Vector2::dot(&t.x, b), Vector2::dot(&t.y, b))
+    }
+
     #[inline]
     pub fn identity() -> Matrix2<T> {
         Matrix2::new(
@@ -136,7 +183,9 @@ impl<T: Float> Matrix2<T> {
     pub fn transpose(m: &Matrix2<T>) -> Matrix2<T> {
         Matrix2::new(Vector2::new(m.x.x, m.y.x), Vector2::new(m.x.y, m.y.y))
     }
+}
 
+impl<T: SignedScalar> Matrix2<T> {
     #[inline]
     pub fn determinant(m: &Matrix2<T>) -> T {
         m.x.x * m.y.y - m.x.y * m.y.x
@@ -148,7 +197,7 @@ impl<T: Float> Matrix2<T> {
     }
 
     pub fn inverse(m: &Matrix2<T>) -> Option<Matrix2<T>> {
-        let det = Matrix2::determinant(&m);
+        let det = Matrix2::determinant(m);
         if det == T::zero() {
             None
         } else {
@@ -159,7 +208,7 @@ impl<T: Float> Matrix2<T> {
     }
 }
 
-impl<T: Float> Matrix3<T> {
+impl<T: Scalar> Matrix3<T> {
     fn mul_matrix_and_matrix(a: &Matrix3<T>, b: &Matrix3<T>) -> Matrix3<T> {
         let t = Matrix3::transpose(a);
         Matrix3::new(
@@ -181,6 +230,15 @@ impl<T: Float> Matrix3<T> {
         )
     }
 
+    fn mul_matrix_and_vector(a: &Matrix3<T>, b: &Vector3<T>) -> Vector3<T> {
+        let t = Matrix3::transpose(a);
+        Vector3::new(
+            Vector3::dot(&t.x, b),
+            Vector3::dot(&t.y, b),
+            Vector3::dot(&t.z, b),
+        )
+    }
+
     #[inline]
     pub fn identity() -> Matrix3<T> {
         Matrix3::new(
@@ -199,14 +257,46 @@ impl<T: Float> Matrix3<T> {
         )
     }
 
+    #[inline]
+    pub fn translate(v: &Vector2<T>) -> Matrix3<T> {
+        Matrix3::new(
+            Vector3::new(T::one(), T::zero(), T::zero()),
+            Vector3::new(T::zero(), T::one(), T::zero()),
+            Vector3::new(v.x, v.y, T::one()),
+        )
+    }
+
+    #[inline]
+    pub fn scale(v: &Vector2<T>) -> Matrix3<T> {
+        Matrix3::new(
+            Vector3::new(v.x, T::zero(), T::zero()),
+            Vector3::new(T::zero(), v.y, T::zero()),
+            Vector3::new(T::zero(), T::zero(), T::one()),
+        )
+    }
+
+    #[inline]
+    pub fn transform_point(m: &Matrix3<T>, p: &Vector2<T>) -> Vector2<T> {
+        let v = *m * Vector3::new(p.x, p.y, T::one());
+        Vector2::new(v.x, v.y)
+    }
+
+    #[inline]
+    pub fn transform_vector(m: &Matrix3<T>, d: &Vector2<T>) -> Vector2<T> {
+        let v = *m * Vector3::new(d.x, d.y, T::zero());
+        Vector2::new(v.x, v.y)
+    }
+}
+
+impl<T: SignedScalar> Matrix3<T> {
     pub fn determinant(m: &Matrix3<T>) -> T {
-        let t = Matrix3::transpose(&m);
+        let t = Matrix3::transpose(m);
         let c = Vector3::cross(&t.x, &t.y);
         Vector3::dot(&c, &t.z)
     }
 
     fn adjugate(m: &Matrix3<T>) -> Matrix3<T> {
-        let t = Matrix3::transpose(&m);
+        let t = Matrix3::transpose(m);
         Matrix3::new(
             Vector3::cross(&t.y, &t.z),
             Vector3::cross(&t.z, &t.x),
@@ -215,25 +305,27 @@ impl<T: Float> Matrix3<T> {
     }
 
     pub fn inverse(m: &Matrix3<T>) -> Option<Matrix3<T>> {
-        let det = Matrix3::determinant(&m);
+        let det = Matrix3::determinant(m);
         if det == T::zero() {
             None
         } else {
             let invdet = T::one() / det;
-            let adj = Matrix3::adjugate(&m);
+            let adj = Matrix3::adjugate(m);
             Some(adj * invdet)
         }
     }
 
-    #[inline]
-    pub fn translate(v: &Vector2<T>) -> Matrix3<T> {
+    pub fn ortho(left: T, right: T, bottom: T, top: T) -> Matrix3<T> {
+        let two = T::one() + T::one();
         Matrix3::new(
-            Vector3::new(T::one(), T::zero(), T::zero()),
-            Vector3::new(T::zero(), T::one(), T::zero()),
-            Vector3::new(v.x, v.y, T::one()),
+            Vector3::new(two / (right - left), T::zero(), T::zero()),
+            Vector3::new(T::zero(), two / (top - bottom), T::zero()),
+            Vector3::new(T::zero(), T::zero(), -T::one()),
         )
     }
+}
 
+impl<T: Real> Matrix3<T> {
     pub fn rotate(rad: T) -> Matrix3<T> {
         let (s, c) = rad.sin_cos();
         Matrix3::new(
@@ -242,29 +334,21 @@ impl<T: Float> Matrix3<T> {
             Vector3::new(T::zero(), T::zero(), T::one()),
         )
     }
+}
 
+impl<T: Scalar> Matrix4<T> {
     #[inline]
-    pub fn scale(v: &Vector2<T>) -> Matrix3<T> {
-        Matrix3::new(
-            Vector3::new(v.x, T::zero(), T::zero()),
-            Vector3::new(T::zero(), v.y, T::zero()),
-            Vector3::new(T::zero(), T::zero(), T::one()),
-        )
+    fn mul_matrix_and_matrix(a: &Matrix4<T>, b: &Matrix4<T>) -> Matrix4<T> {
+        T::mul_matrix4_and_matrix4(a, b)
     }
 
-    pub fn ortho(left: T, right: T, bottom: T, top: T) -> Matrix3<T> {
-        let two: T = cast(2).unwrap();
-        Matrix3::new(
-            Vector3::new(two / (right - left), T::zero(), T::zero()),
-            Vector3::new(T::zero(), two / (top - bottom), T::zero()),
-            Vector3::new(T::zero(), T::zero(), -T::one()),
-        )
+    #[inline]
+    fn mul_matrix_and_vector(a: &Matrix4<T>, b: &Vector4<T>) -> Vector4<T> {
+        T::mul_matrix4_and_vector4(a, b)
     }
-}
 
-impl<T: Float> Matrix4<T> {
-    fn mul_matrix_and_matrix(a: &Matrix4<T>, b: &Matrix4<T>) -> Matrix4<T> {
-        let t = Matrix4::transpose(a);
+    pub(crate) fn mul_matrix_and_matrix_fallback(a: &Matrix4<T>, b: &Matrix4<T>) -> Matrix4<T> {
+        let t = Matrix4::transpose_fallback(a);
         Matrix4::new(
             Vector4::new(
                 Vector4::dot(&t.x, &b.x),
@@ -293,6 +377,16 @@ impl<T: Float> Matrix4<T> {
         )
     }
 
+    pub(crate) fn mul_matrix_and_vector_fallback(a: &Matrix4<T>, b: &Vector4<T>) -> Vector4<T> {
+        let t = Matrix4::transpose_fallback(a);
+        Vector4::new(
+            Vector4::dot(&t.x, b),
+            Vector4::dot(&t.y, b),
+            Vector4::dot(&t.z, b),
+            Vector4::dot(&t.w, b),
+        )
+    }
+
     #[inline]
     pub fn identity() -> Matrix4<T> {
         Matrix4::new(
@@ -305,6 +399,11 @@ impl<T: Float> Matrix4<T> {
 
     #[inline]
     pub fn transpose(m: &Matrix4<T>) -> Matrix4<T> {
+        T::transpose_matrix4(m)
+    }
+
+    #[inline]
+    pub(crate) fn transpose_fallback(m: &Matrix4<T>) -> Matrix4<T> {
         Matrix4::new(
             Vector4::new(m.x.x, m.y.x, m.z.x, m.w.x),
             Vector4::new(m.x.y, m.y.y, m.z.y, m.w.y),
@@ -313,6 +412,40 @@ impl<T: Float> Matrix4<T> {
         )
     }
 
+    #[inline]
+    pub fn translate(v: &Vector3<T>) -> Matrix4<T> {
+        Matrix4::new(
+            Vector4::new(T::one(), T::zero(), T::zero(), T::zero()),
+            Vector4::new(T::zero(), T::one(), T::zero(), T::zero()),
+            Vector4::new(T::zero(), T::zero(), T::one(), T::zero()),
+            Vector4::new(v.x, v.y, v.z, T::one()),
+        )
+    }
+
+    #[inline]
+    pub fn scale(v: &Vector3<T>) -> Matrix4<T> {
+        Matrix4::new(
+            Vector4::new(v.x, T::zero(), T::zero(), T::zero()),
+            Vector4::new(T::zero(), v.y, T::zero(), T::zero()),
+            Vector4::new(T::zero(), T::zero(), v.z, T::zero()),
+            Vector4::new(T::zero(), T::zero(), T::zero(), T::one()),
+        )
+    }
+
+    #[inline]
+    pub fn transform_point(m: &Matrix4<T>, p: &Vector3<T>) -> Vector3<T> {
+        let v = *m * Vector4::new(p.x, p.y, p.z, T::one());
+        Vector3::new(v.x, v.y, v.z)
+    }
+
+    #[inline]
+    pub fn transform_vector(m: &Matrix4<T>, d: &Vector3<T>) -> Vector3<T> {
+        let v = *m * Vector4::new(d.x, d.y, d.z, T::zero());
+        Vector3::new(v.x, v.y, v.z)
+    }
+}
+
+impl<T: SignedScalar> Matrix4<T> {
     pub fn determinant(m: &Matrix4<T>) -> T {
         let a = Matrix3::new(
             Vector3::new(m.y.y, m.y.z, m.y.w),
@@ -449,31 +582,58 @@ impl<T: Float> Matrix4<T> {
     }
 
     fn adjugate(m: &Matrix4<T>) -> Matrix4<T> {
-        let c = Matrix4::comatrix(&m);
+        let c = Matrix4::comatrix(m);
         Matrix4::transpose(&c)
     }
 
+    #[inline]
     pub fn inverse(m: &Matrix4<T>) -> Option<Matrix4<T>> {
-        let det = Matrix4::determinant(&m);
+        T::inverse_matrix4(m)
+    }
+
+    pub(crate) fn inverse_fallback(m: &Matrix4<T>) -> Option<Matrix4<T>> {
+        let det = Matrix4::determinant(m);
         if det == T::zero() {
             None
         } else {
             let invdet = T::one() / det;
-            let adj = Matrix4::adjugate(&m);
+            let adj = Matrix4::adjugate(m);
             Some(adj * invdet)
         }
     }
 
-    #[inline]
-    pub fn translate(v: &Vector3<T>) -> Matrix4<T> {
+    pub fn ortho(left: T, right: T, bottom: T, top: T, near: T, far: T) -> Matrix4<T> {
+        let two = T::one() + T::one();
         Matrix4::new(
-            Vector4::new(T::one(), T::zero(), T::zero(), T::zero()),
-            Vector4::new(T::zero(), T::one(), T::zero(), T::zero()),
-            Vector4::new(T::zero(), T::zero(), T::one(), T::zero()),
-            Vector4::new(v.x, v.y, v.z, T::one()),
+            Vector4::new(
+                two / (right - left),
+                T::zero(),
+                T::zero(),
+                T::zero(),
+            ),
+            Vector4::new(
+                T::zero(),
+                two / (top - bottom),
+                T::zero(),
+                T::zero(),
+            ),
+            Vector4::new(
+                T::zero(),
+                T::zero(),
+                -two / (far - near),
+                T::zero(),
+            ),
+            Vector4::new(
+                -(right + left) / (right - left),
+                -(top + bottom) / (top - bottom),
+                -(far + near) / (far - near),
+                T::one()
+            ),
         )
     }
+}
 
+impl<T: Real> Matrix4<T> {
     pub fn rotate(rad: T, v: &Vector3<T>) -> Matrix4<T> {
         let (s, c) = rad.sin_cos();
         let axis = Vector3::normalize(v);
@@ -501,64 +661,25 @@ impl<T: Float> Matrix4<T> {
         )
     }
 
-    #[inline]
-    pub fn scale(v: &Vector3<T>) -> Matrix4<T> {
-        Matrix4::new(
-            Vector4::new(v.x, T::zero(), T::zero(), T::zero()),
-            Vector4::new(T::zero(), v.y, T::zero(), T::zero()),
-            Vector4::new(T::zero(), T::zero(), v.z, T::zero()),
-            Vector4::new(T::zero(), T::zero(), T::zero(), T::one()),
-        )
-    }
-
-    pub fn ortho(left: T, right: T, bottom: T, top: T, near: T, far: T) -> Matrix4<T> {
-        let two: T = cast(2).unwrap();
-        Matrix4::new(
-            Vector4::new(
-                two / (right - left),
-                T::zero(),
-                T::zero(),
-                T::zero(),
-            ),
-            Vector4::new(
-                T::zero(),
-                two / (top - bottom),
-                T::zero(),
-                T::zero(),
-            ),
-            Vector4::new(
-                T::zero(),
-                T::zero(),
-                -two / (far - near),
-                T::zero(),
-            ),
-            Vector4::new(
-                -(right + left) / (right - left),
-                -(top + bottom) / (top - bottom),
-                -(far + near) / (far - near),
-                T::one()
-            ),
-        )
-    }
-
     pub fn perspective(fovy: T, aspect: T, near: T, far: T) -> Matrix4<T> {
-        let two: T = cast(2).unwrap();
+        let two = T::one() + T::one();
         let f = (fovy / two).tan().recip();
         Matrix4::new(
             Vector4::new(f / aspect, T::zero(), T::zero(), T::zero()),
             Vector4::new(T::zero(), f, T::zero(), T::zero()),
+            Vector4::new(T::zero(), T::zero(), (far + near) / (near - far), -T::one()),
             Vector4::new(
                 T::zero(),
                 T::zero(),
-                (far + near) / (near - far),
                 (two * far * near) / (near - far),
+                T::zero(),
             ),
-            Vector4::new(T::zero(), T::zero(), -T::one(), T::zero()),
         )
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod tests {
     use super::*;
 
@@ -1074,6 +1195,15 @@ mod tests {
         assert_ulps_eq!(r * d, Vector4::new(0.0, 1.0, 0.0, 0.0));
     }
 
+    #[test]
+    fn matrix4_perspective() {
+        let p = Matrix4::perspective(FRAC_PI_2, 2.0, 1.0, 10.0);
+        let near = p * Vector4::new(1.0, 0.5, -1.0, 1.0);
+        assert_ulps_eq!(near * (1.0 / near.w), Vector4::new(0.5, 0.5, -1.0, 1.0));
+        let far = p * Vector4::new(0.0, -10.0, -10.0, 1.0);
+        assert_ulps_eq!(far * (1.0 / far.w), Vector4::new(0.0, -1.0, 1.0, 1.0));
+    }
+
     #[test]
     fn matrix4_scale() {
         let s = Matrix4::scale(&Vector3::new(2.0, 1.0, 1.0));
@@ -1082,4 +1212,22 @@ mod tests {
         assert_eq!(s * p, Vector4::new(2.0, 0.0, 0.0, 1.0));
         assert_eq!(s * d, Vector4::new(2.0, 0.0, 0.0, 0.0));
     }
+
+    #[test]
+    fn matrix3_transform_point_and_vector() {
+        let m =
+            Matrix3::translate(&Vector2::new(1.0, 2.0)) * Matrix3::scale(&Vector2::new(2.0, 3.0));
+        let v = Vector2::new(1.0, 1.0);
+        assert_eq!(Matrix3::transform_point(&m, &v), Vector2::new(3.0, 5.0));
+        assert_eq!(Matrix3::transform_vector(&m, &v), Vector2::new(2.0, 3.0));
+    }
+
+    #[test]
+    fn matrix4_transform_point_and_vector() {
+        let m = Matrix4::translate(&Vector3::new(1.0, 2.0, 3.0))
+            * Matrix4::scale(&Vector3::new(2.0, 3.0, 4.0));
+        let v = Vector3::new(1.0, 1.0, 1.0);
+        assert_eq!(Matrix4::transform_point(&m, &v), Vector3::new(3.0, 5.0, 7.0));
+        assert_eq!(Matrix4::transform_vector(&m, &v), Vector3::new(2.0, 3.0, 4.0));
+    }
 }
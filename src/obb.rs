@@ -0,0 +1,312 @@
+use crate::aabb::Aabb3;
+use crate::matrix::*;
+use crate::scalar::Real;
+use crate::vector::*;
+use core::array;
+
+/// Oriented box: `center` plus up to `half_extents` along each of the orthonormal columns
+/// of `axes`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Obb3<T> {
+    pub center: Vector3<T>,
+    pub axes: Matrix3<T>,
+    pub half_extents: Vector3<T>,
+}
+
+impl<T> Obb3<T> {
+    #[inline]
+    pub fn new(center: Vector3<T>, axes: Matrix3<T>, half_extents: Vector3<T>) -> Obb3<T> {
+        Obb3 {
+            center,
+            axes,
+            half_extents,
+        }
+    }
+}
+
+#[inline]
+fn components<T: Copy>(v: &Vector3<T>) -> [T; 3] {
+    [v.x, v.y, v.z]
+}
+
+// Eigenvectors of the symmetric `m` as the columns of a rotation, by cyclic Jacobi sweeps.
+fn symmetric_eigenvectors<T: Real>(m: &Matrix3<T>) -> Matrix3<T> {
+    let mut a = [components(&m.x), components(&m.y), components(&m.z)];
+    let mut v = [[T::zero(); 3]; 3];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = T::one();
+    }
+    for _ in 0..32 {
+        let off = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+        let diag = a[0][0] * a[0][0] + a[1][1] * a[1][1] + a[2][2] * a[2][2];
+        if off <= diag * T::from_f64(1e-24) {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q] == T::zero() {
+                continue;
+            }
+            // The rotation by `atan(t)` in the p-q plane zeroing `a[p][q]`.
+            let theta = (a[q][q] - a[p][p]) / ((T::one() + T::one()) * a[p][q]);
+            let t = (theta.abs() + (theta * theta + T::one()).sqrt()).recip();
+            let t = if theta < T::zero() { -t } else { t };
+            let c = (t * t + T::one()).sqrt().recip();
+            let s = t * c;
+            for row in a.iter_mut() {
+                let (x, y) = (row[p], row[q]);
+                row[p] = c * x - s * y;
+                row[q] = s * x + c * y;
+            }
+            let (row_p, row_q) = (a[p], a[q]);
+            a[p] = array::from_fn(|k| c * row_p[k] - s * row_q[k]);
+            a[q] = array::from_fn(|k| s * row_p[k] + c * row_q[k]);
+            for row in v.iter_mut() {
+                let (x, y) = (row[p], row[q]);
+                row[p] = c * x - s * y;
+                row[q] = s * x + c * y;
+            }
+        }
+    }
+    let x = Vector3::new(v[0][0], v[1][0], v[2][0]);
+    let y = Vector3::new(v[0][1], v[1][1], v[2][1]);
+    Matrix3::new(x, y, Vector3::cross(&x, &y))
+}
+
+impl<T: Real> Obb3<T> {
+    #[inline]
+    pub fn from_aabb(b: &Aabb3<T>) -> Obb3<T> {
+        let half = T::from_f64(0.5);
+        Obb3::new(
+            Aabb3::center(b),
+            Matrix3::identity(),
+            Aabb3::extents(b) * half,
+        )
+    }
+
+    /// Box aligned with the principal axes of the points, usually but not always tighter
+    /// than their `Aabb3`. `None` if there are no points.
+    pub fn from_points(points: &[Vector3<T>]) -> Option<Obb3<T>> {
+        let first = points.first()?;
+        let n = T::from_f64(points.len() as f64).recip();
+        let mean = points.iter().fold(Vector3::splat(T::zero()), |m, p| m + *p) * n;
+        let mut cov = Matrix3::new(
+            Vector3::splat(T::zero()),
+            Vector3::splat(T::zero()),
+            Vector3::splat(T::zero()),
+        );
+        for p in points {
+            let d = *p - mean;
+            cov += Matrix3::new(d * d.x, d * d.y, d * d.z);
+        }
+        let axes = symmetric_eigenvectors(&(cov * n));
+
+        // Bounds in the frame of the axes.
+        let t = Matrix3::transpose(&axes);
+        let local = |p: &Vector3<T>| t * *p;
+        let bounds = points[1..]
+            .iter()
+            .fold(Aabb3::new(local(first), local(first)), |b, p| {
+                Aabb3::extend(&b, &local(p))
+            });
+        Some(Obb3::new(
+            axes * Aabb3::center(&bounds),
+            axes,
+            Aabb3::extents(&bounds) * T::from_f64(0.5),
+        ))
+    }
+
+    /// Maps the box `-half_extents..=half_extents` onto `o`.
+    #[inline]
+    pub fn matrix(o: &Obb3<T>) -> Matrix4<T> {
+        let column = |v: &Vector3<T>, w: T| Vector4::new(v.x, v.y, v.z, w);
+        Matrix4::new(
+            column(&o.axes.x, T::zero()),
+            column(&o.axes.y, T::zero()),
+            column(&o.axes.z, T::zero()),
+            column(&o.center, T::one()),
+        )
+    }
+
+    pub fn corners(o: &Obb3<T>) -> [Vector3<T>; 8] {
+        let e = o.half_extents;
+        let (x, y, z) = (o.axes.x * e.x, o.axes.y * e.y, o.axes.z * e.z);
+        let c = o.center;
+        [
+            c - x - y - z,
+            c + x - y - z,
+            c - x + y - z,
+            c + x + y - z,
+            c - x - y + z,
+            c + x - y + z,
+            c - x + y + z,
+            c + x + y + z,
+        ]
+    }
+
+    #[inline]
+    pub fn contains(o: &Obb3<T>, p: &Vector3<T>) -> bool {
+        let d = Matrix3::transpose(&o.axes) * (*p - o.center);
+        d.x.abs() <= o.half_extents.x
+            && d.y.abs() <= o.half_extents.y
+            && d.z.abs() <= o.half_extents.z
+    }
+
+    /// Separating axis test over the 15 candidate axes.
+    pub fn intersects(a: &Obb3<T>, b: &Obb3<T>) -> bool {
+        let a_axes = [a.axes.x, a.axes.y, a.axes.z];
+        let b_axes = [b.axes.x, b.axes.y, b.axes.z];
+        let ae = components(&a.half_extents);
+        let be = components(&b.half_extents);
+
+        // `b` in the frame of `a`. The epsilon keeps nearly parallel edges, whose cross
+        // products are close to zero, from producing false separations.
+        let eps = T::from_f64(1e-6);
+        let mut r = [[T::zero(); 3]; 3];
+        let mut abs_r = [[T::zero(); 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                r[i][j] = Vector3::dot(&a_axes[i], &b_axes[j]);
+                abs_r[i][j] = r[i][j].abs() + eps;
+            }
+        }
+        let d = b.center - a.center;
+        let t = a_axes.map(|axis| Vector3::dot(&d, &axis));
+
+        for i in 0..3 {
+            let rb = be[0] * abs_r[i][0] + be[1] * abs_r[i][1] + be[2] * abs_r[i][2];
+            if t[i].abs() > ae[i] + rb {
+                return false;
+            }
+        }
+        for j in 0..3 {
+            let ra = ae[0] * abs_r[0][j] + ae[1] * abs_r[1][j] + ae[2] * abs_r[2][j];
+            let dist = t[0] * r[0][j] + t[1] * r[1][j] + t[2] * r[2][j];
+            if dist.abs() > ra + be[j] {
+                return false;
+            }
+        }
+        for i in 0..3 {
+            let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+            for j in 0..3 {
+                let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+                let ra = ae[i1] * abs_r[i2][j] + ae[i2] * abs_r[i1][j];
+                let rb = be[j1] * abs_r[i][j2] + be[j2] * abs_r[i][j1];
+                let dist = t[i2] * r[i1][j] - t[i1] * r[i2][j];
+                if dist.abs() > ra + rb {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// The box transformed by `m`, which must be a rotation, translation and scale along
+    /// the box axes.
+    pub fn transform(m: &Matrix4<T>, o: &Obb3<T>) -> Obb3<T> {
+        let axis = |v: &Vector3<T>| {
+            let v = Matrix4::transform_vector(m, v);
+            let l = Vector3::length(&v);
+            (v * l.recip(), l)
+        };
+        let (x, sx) = axis(&o.axes.x);
+        let (y, sy) = axis(&o.axes.y);
+        let (z, sz) = axis(&o.axes.z);
+        let e = o.half_extents;
+        Obb3::new(
+            Matrix4::transform_point(m, &o.center),
+            Matrix3::new(x, y, z),
+            Vector3::new(e.x * sx, e.y * sy, e.z * sz),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_4};
+
+    fn rotated(center: Vector3<f64>, angle: f64, half_extents: Vector3<f64>) -> Obb3<f64> {
+        let r = Matrix4::rotate(angle, &Vector3::new(0.0, 0.0, 1.0));
+        let axes = Matrix3::new(
+            Vector3::new(r.x.x, r.x.y, r.x.z),
+            Vector3::new(r.y.x, r.y.y, r.y.z),
+            Vector3::new(r.z.x, r.z.y, r.z.z),
+        );
+        Obb3::new(center, axes, half_extents)
+    }
+
+    #[test]
+    fn obb_from_points() {
+        // A 4 x 1 x 0.5 box rotated by 45 degrees around z.
+        let o = rotated(
+            Vector3::new(1.0, 2.0, 3.0),
+            FRAC_PI_4,
+            Vector3::new(2.0, 0.5, 0.25),
+        );
+        let fitted = Obb3::from_points(&Obb3::corners(&o)).unwrap();
+        assert_relative_eq!(fitted.center, o.center, epsilon = 1e-9);
+        assert_relative_eq!(Matrix3::determinant(&fitted.axes), 1.0, epsilon = 1e-9);
+        let mut e = components(&fitted.half_extents);
+        e.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_relative_eq!(e[..], [0.25, 0.5, 2.0][..], epsilon = 1e-9);
+        for p in Obb3::corners(&o) {
+            let q = p + (o.center - p) * 1e-6;
+            assert!(Obb3::contains(&fitted, &q));
+        }
+        assert_eq!(Obb3::<f64>::from_points(&[]), None);
+    }
+
+    #[test]
+    fn obb_intersects() {
+        let a = Obb3::from_aabb(&Aabb3::new(
+            Vector3::new(-1.0, -1.0, -1.0),
+            Vector3::new(1.0, 1.0, 1.0),
+        ));
+        // Rotated around z, `b` reaches sqrt(2) from its center along x.
+        let b = rotated(
+            Vector3::new(2.3, 0.0, 0.0),
+            FRAC_PI_4,
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        assert!(Obb3::intersects(&a, &b));
+        let b = rotated(
+            Vector3::new(2.5, 0.0, 0.0),
+            FRAC_PI_4,
+            Vector3::new(1.0, 1.0, 1.0),
+        );
+        assert!(!Obb3::intersects(&a, &b));
+        // An edge of `b` along (1, -1, 0) faces the edge of `a` along z across the diagonal
+        // (1, 1, 0). Only their cross product separates the boxes.
+        let h = FRAC_1_SQRT_2;
+        let axes = Matrix3::new(
+            Vector3::new(h, -h, 0.0),
+            Vector3::new(0.5, 0.5, h),
+            Vector3::new(0.5, 0.5, -h),
+        );
+        let diagonal = Vector3::new(h, h, 0.0);
+        let b = Obb3::new(diagonal * 2.9, axes, Vector3::new(1.0, 1.0, 1.0));
+        assert!(!Obb3::intersects(&a, &b));
+        let b = Obb3::new(diagonal * 2.7, axes, Vector3::new(1.0, 1.0, 1.0));
+        assert!(Obb3::intersects(&a, &b));
+        assert!(Obb3::intersects(&a, &a));
+    }
+
+    #[test]
+    fn obb_transform() {
+        let o = rotated(
+            Vector3::new(1.0, 0.0, 0.0),
+            0.3,
+            Vector3::new(1.0, 2.0, 3.0),
+        );
+        let m = Matrix4::translate(&Vector3::new(0.0, 5.0, 0.0))
+            * Matrix4::rotate(1.1, &Vector3::new(1.0, 2.0, 3.0))
+            * Matrix4::scale(&Vector3::new(2.0, 2.0, 2.0));
+        let t = Obb3::transform(&m, &o);
+        for (p, q) in Obb3::corners(&o).iter().zip(&Obb3::corners(&t)) {
+            assert_relative_eq!(Matrix4::transform_point(&m, p), *q, epsilon = 1e-12);
+        }
+        let u = Matrix4::transform_point(&Obb3::matrix(&t), &t.half_extents);
+        assert_relative_eq!(u, Obb3::corners(&t)[7], epsilon = 1e-12);
+    }
+}
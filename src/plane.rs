@@ -0,0 +1,142 @@
+use crate::matrix::Matrix4;
+use crate::scalar::{Real, SignedScalar};
+use crate::vector::*;
+
+/// The points `p` with `dot(normal, p) + d = 0`. The normal points to the positive side.
+/// Distances are in units of its length, exact for planes built by `from_points` or
+/// `normalize`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Plane<T> {
+    pub normal: Vector3<T>,
+    pub d: T,
+}
+
+impl<T> Plane<T> {
+    #[inline]
+    pub fn new(normal: Vector3<T>, d: T) -> Plane<T> {
+        Plane { normal, d }
+    }
+}
+
+impl<T: SignedScalar> Plane<T> {
+    #[inline]
+    pub fn from_point_normal(p: &Vector3<T>, normal: &Vector3<T>) -> Plane<T> {
+        Plane::new(*normal, -Vector3::dot(normal, p))
+    }
+
+    #[inline]
+    pub fn signed_distance(pl: &Plane<T>, p: &Vector3<T>) -> T {
+        Vector3::dot(&pl.normal, p) + pl.d
+    }
+
+    /// The point of the plane closest to `p`.
+    #[inline]
+    pub fn project_point(pl: &Plane<T>, p: &Vector3<T>) -> Vector3<T> {
+        let n2 = Vector3::dot(&pl.normal, &pl.normal);
+        *p - pl.normal * (Plane::signed_distance(pl, p) / n2)
+    }
+
+    /// The plane transformed by the affine `m`, using the inverse-transpose of `m`. Returns
+    /// `None` if `m` is singular. The result is not normalized.
+    pub fn transform(m: &Matrix4<T>, pl: &Plane<T>) -> Option<Plane<T>> {
+        let it = Matrix4::transpose(&Matrix4::inverse(m)?);
+        let v = it * Vector4::new(pl.normal.x, pl.normal.y, pl.normal.z, pl.d);
+        Some(Plane::new(Vector3::new(v.x, v.y, v.z), v.w))
+    }
+}
+
+impl<T: Real> Plane<T> {
+    /// The plane through `a`, `b` and `c`, with the normal on the side from which they
+    /// appear counter-clockwise.
+    #[inline]
+    pub fn from_points(a: &Vector3<T>, b: &Vector3<T>, c: &Vector3<T>) -> Plane<T> {
+        let n = Vector3::normalize(&Vector3::cross(&(*b - *a), &(*c - *a)));
+        Plane::from_point_normal(a, &n)
+    }
+
+    #[inline]
+    pub fn normalize(pl: &Plane<T>) -> Plane<T> {
+        let s = Vector3::length(&pl.normal).recip();
+        Plane::new(pl.normal * s, pl.d * s)
+    }
+}
+
+impl<T: Real> Matrix4<T> {
+    /// Mirrors points across `pl`.
+    pub fn reflection(pl: &Plane<T>) -> Matrix4<T> {
+        let Plane { normal: n, d } = Plane::normalize(pl);
+        let m2 = -(T::one() + T::one());
+        let col = |a: T| n * (m2 * a);
+        let (x, y, z, w) = (col(n.x), col(n.y), col(n.z), col(d));
+        Matrix4::new(
+            Vector4::new(T::one() + x.x, x.y, x.z, T::zero()),
+            Vector4::new(y.x, T::one() + y.y, y.z, T::zero()),
+            Vector4::new(z.x, z.y, T::one() + z.z, T::zero()),
+            Vector4::new(w.x, w.y, w.z, T::one()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn plane_construction() {
+        let pl = Plane::from_points(
+            &Vector3::new(0.0, 0.0, 2.0),
+            &Vector3::new(1.0, 0.0, 2.0),
+            &Vector3::new(0.0, 1.0, 2.0),
+        );
+        assert_relative_eq!(pl.normal, Vector3::new(0.0, 0.0, 1.0));
+        assert_relative_eq!(pl.d, -2.0);
+        let p = Vector3::new(3.0, -1.0, 5.0);
+        assert_relative_eq!(Plane::signed_distance(&pl, &p), 3.0);
+        assert_relative_eq!(Plane::project_point(&pl, &p), Vector3::new(3.0, -1.0, 2.0));
+
+        let unnormalized =
+            Plane::from_point_normal(&Vector3::new(0.0, 0.0, 2.0), &Vector3::new(0.0, 0.0, 4.0));
+        assert_relative_eq!(
+            Plane::project_point(&unnormalized, &p),
+            Vector3::new(3.0, -1.0, 2.0)
+        );
+        let n = Plane::normalize(&unnormalized);
+        assert_relative_eq!(n.normal, pl.normal);
+        assert_relative_eq!(n.d, pl.d);
+    }
+
+    #[test]
+    fn plane_reflection() {
+        let pl =
+            Plane::from_point_normal(&Vector3::new(1.0, 0.0, 0.0), &Vector3::new(2.0, 0.0, 0.0));
+        let m = Matrix4::reflection(&pl);
+        assert_relative_eq!(
+            Matrix4::transform_point(&m, &Vector3::new(3.0, 1.0, 2.0)),
+            Vector3::new(-1.0, 1.0, 2.0)
+        );
+        assert_relative_eq!(m * m, Matrix4::identity());
+        assert_relative_eq!(Matrix4::determinant(&m), -1.0);
+    }
+
+    #[test]
+    fn plane_transform() {
+        let pl =
+            Plane::from_point_normal(&Vector3::new(0.0, 0.0, 1.0), &Vector3::new(0.0, 0.0, 1.0));
+        let m = Matrix4::translate(&Vector3::new(0.0, 5.0, 0.0))
+            * Matrix4::rotate(FRAC_PI_2, &Vector3::new(1.0, 0.0, 0.0))
+            * Matrix4::scale(&Vector3::new(1.0, 1.0, 3.0));
+        let t = Plane::normalize(&Plane::transform(&m, &pl).unwrap());
+        // Points of the plane map to points of the transformed plane.
+        for p in [Vector3::new(0.0, 0.0, 1.0), Vector3::new(2.0, -1.0, 1.0)] {
+            let q = Matrix4::transform_point(&m, &p);
+            assert_relative_eq!(Plane::signed_distance(&t, &q), 0.0, epsilon = 1e-12);
+        }
+        assert_relative_eq!(t.normal, Vector3::new(0.0, -1.0, 0.0), epsilon = 1e-12);
+        assert_eq!(
+            Plane::transform(&Matrix4::scale(&Vector3::new(1.0, 0.0, 1.0)), &pl),
+            None
+        );
+    }
+}
@@ -0,0 +1,655 @@
+//! Robust geometric predicates after Shewchuk: the sign of each result is exact, found by
+//! a floating point evaluation with a forward error bound, falling back to exact expansion
+//! arithmetic on the raw coordinates when the bound cannot decide. `incircle` and
+//! `insphere` have an intermediate stage that evaluates the determinant of the rounded
+//! coordinate differences exactly, which settles nearly all remaining cases with much
+//! smaller expansions than the fully exact fallback.
+//!
+//! Inputs are assumed finite and far enough from overflow and underflow.
+
+use crate::vector::*;
+
+// Half an ulp of one, the relative rounding error of a single operation.
+const EPSILON: f64 = f64::EPSILON * 0.5;
+const SPLITTER: f64 = 134_217_729.0; // 2^27 + 1
+
+const CCW_ERRBOUND_A: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const O3D_ERRBOUND_A: f64 = (7.0 + 56.0 * EPSILON) * EPSILON;
+const ICC_ERRBOUND_A: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+const ISP_ERRBOUND_A: f64 = (16.0 + 224.0 * EPSILON) * EPSILON;
+const ICC_ERRBOUND_B: f64 = (4.0 + 48.0 * EPSILON) * EPSILON;
+const ISP_ERRBOUND_B: f64 = (5.0 + 72.0 * EPSILON) * EPSILON;
+
+#[inline]
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let bv = x - a;
+    let av = x - bv;
+    (x, (a - av) + (b - bv))
+}
+
+// The rounding error of `a - b`.
+#[inline]
+fn diff_tail(a: f64, b: f64) -> f64 {
+    two_sum(a, -b).1
+}
+
+#[inline]
+fn split(a: f64) -> (f64, f64) {
+    let c = SPLITTER * a;
+    let hi = c - (c - a);
+    (hi, a - hi)
+}
+
+#[inline]
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    let (ahi, alo) = split(a);
+    let (bhi, blo) = split(b);
+    let err = x - ahi * bhi - alo * bhi - ahi * blo;
+    (x, alo * blo - err)
+}
+
+// A sum of non-overlapping components in increasing order of magnitude, whose largest
+// component has the sign of the exact value.
+#[derive(Copy, Clone, Debug)]
+struct Expansion<const N: usize> {
+    c: [f64; N],
+    len: usize,
+}
+
+impl<const N: usize> Expansion<N> {
+    #[inline]
+    fn new(a: f64) -> Expansion<N> {
+        let mut c = [0.0; N];
+        c[0] = a;
+        Expansion { c, len: 1 }
+    }
+
+    #[inline]
+    fn product(a: f64, b: f64) -> Expansion<N> {
+        let (x, y) = two_product(a, b);
+        let mut c = [0.0; N];
+        c[0] = y;
+        c[1] = x;
+        Expansion { c, len: 2 }
+    }
+
+    #[inline]
+    fn components(&self) -> &[f64] {
+        &self.c[..self.len]
+    }
+
+    #[inline]
+    fn negate(mut self) -> Expansion<N> {
+        for c in &mut self.c[..self.len] {
+            *c = -*c;
+        }
+        self
+    }
+
+    // The component of largest magnitude, approximating the value.
+    #[inline]
+    fn estimate(&self) -> f64 {
+        self.c[self.len - 1]
+    }
+
+    // Shewchuk's fast expansion sum with zero elimination.
+    fn sum<const A: usize, const B: usize>(e: &Expansion<A>, f: &Expansion<B>) -> Expansion<N> {
+        let (e, f) = (e.components(), f.components());
+        let mut h = Expansion {
+            c: [0.0; N],
+            len: 0,
+        };
+        let (mut i, mut j) = (0, 0);
+        // The next component of either input in increasing order of magnitude.
+        let next = |i: &mut usize, j: &mut usize| {
+            if *j == f.len() || (*i < e.len() && (f[*j] > e[*i]) == (f[*j] > -e[*i])) {
+                *i += 1;
+                e[*i - 1]
+            } else {
+                *j += 1;
+                f[*j - 1]
+            }
+        };
+        let mut q = next(&mut i, &mut j);
+        while i < e.len() || j < f.len() {
+            let (x, y) = two_sum(q, next(&mut i, &mut j));
+            q = x;
+            if y != 0.0 {
+                h.c[h.len] = y;
+                h.len += 1;
+            }
+        }
+        if q != 0.0 || h.len == 0 {
+            h.c[h.len] = q;
+            h.len += 1;
+        }
+        h
+    }
+
+    // Shewchuk's expansion scaling with zero elimination.
+    fn scale<const A: usize>(e: &Expansion<A>, b: f64) -> Expansion<N> {
+        let mut h = Expansion {
+            c: [0.0; N],
+            len: 0,
+        };
+        let push = |h: &mut Expansion<N>, x: f64| {
+            if x != 0.0 {
+                h.c[h.len] = x;
+                h.len += 1;
+            }
+        };
+        let (mut q, low) = two_product(e.c[0], b);
+        push(&mut h, low);
+        for &x in &e.components()[1..] {
+            let (hi, lo) = two_product(x, b);
+            let (sum, err) = two_sum(q, lo);
+            push(&mut h, err);
+            let (sum, err) = two_sum(hi, sum);
+            push(&mut h, err);
+            q = sum;
+        }
+        if q != 0.0 || h.len == 0 {
+            h.c[h.len] = q;
+            h.len += 1;
+        }
+        h
+    }
+
+    fn mul<const A: usize, const B: usize>(e: &Expansion<A>, f: &Expansion<B>) -> Expansion<N> {
+        f.components().iter().fold(Expansion::new(0.0), |acc, &x| {
+            Expansion::sum(&acc, &Expansion::<N>::scale(e, x))
+        })
+    }
+}
+
+// `a.x * b.y - a.y * b.x` exactly.
+#[inline]
+fn cross2(a: &Vector2<f64>, b: &Vector2<f64>) -> Expansion<4> {
+    Expansion::sum(
+        &Expansion::<2>::product(a.x, b.y),
+        &Expansion::<2>::product(a.y, b.x).negate(),
+    )
+}
+
+/// Positive if `a`, `b` and `c` are in counter-clockwise order, negative if clockwise
+/// and zero if collinear. The magnitude approximates twice the signed area of the
+/// triangle.
+pub fn orient2d(a: &Vector2<f64>, b: &Vector2<f64>, c: &Vector2<f64>) -> f64 {
+    let left = (a.x - c.x) * (b.y - c.y);
+    let right = (a.y - c.y) * (b.x - c.x);
+    let det = left - right;
+    if det.abs() >= CCW_ERRBOUND_A * (left.abs() + right.abs()) {
+        return det;
+    }
+    let ab = cross2(a, b);
+    let bc = cross2(b, c);
+    let ca = cross2(c, a);
+    Expansion::<12>::sum(&Expansion::<8>::sum(&ab, &bc), &ca).estimate()
+}
+
+// The exact determinant of the 4x4 matrix with rows `(x, y, w, 1)`, by Laplace expansion
+// along the first two columns. `N` must hold 96 times the longest `w`.
+fn lifted_det4<const N: usize>(xy: [(f64, f64); 4], w: &[Expansion<N>; 4]) -> Expansion<N> {
+    const TERMS: [(usize, usize, usize, usize, bool); 6] = [
+        (0, 1, 2, 3, false),
+        (0, 2, 1, 3, true),
+        (0, 3, 1, 2, false),
+        (1, 2, 0, 3, false),
+        (1, 3, 0, 2, true),
+        (2, 3, 0, 1, false),
+    ];
+    TERMS
+        .iter()
+        .fold(Expansion::new(0.0), |det, &(i, j, k, l, negate)| {
+            let minor = cross2(
+                &Vector2::new(xy[i].0, xy[i].1),
+                &Vector2::new(xy[j].0, xy[j].1),
+            );
+            let w = if negate {
+                Expansion::<N>::sum(&w[l], &w[k].negate())
+            } else {
+                Expansion::<N>::sum(&w[k], &w[l].negate())
+            };
+            Expansion::sum(&det, &Expansion::<N>::mul(&minor, &w))
+        })
+}
+
+/// Positive if `d` is below the plane through `a`, `b` and `c`, where below is the side
+/// from which they appear clockwise, negative if above and zero if the points are
+/// coplanar. The magnitude approximates six times the signed volume of the tetrahedron.
+pub fn orient3d(a: &Vector3<f64>, b: &Vector3<f64>, c: &Vector3<f64>, d: &Vector3<f64>) -> f64 {
+    let (ad, bd, cd) = (*a - *d, *b - *d, *c - *d);
+    let (bdxcdy, cdxbdy) = (bd.x * cd.y, cd.x * bd.y);
+    let (cdxady, adxcdy) = (cd.x * ad.y, ad.x * cd.y);
+    let (adxbdy, bdxady) = (ad.x * bd.y, bd.x * ad.y);
+    let det = ad.z * (bdxcdy - cdxbdy) + bd.z * (cdxady - adxcdy) + cd.z * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * ad.z.abs()
+        + (cdxady.abs() + adxcdy.abs()) * bd.z.abs()
+        + (adxbdy.abs() + bdxady.abs()) * cd.z.abs();
+    if det.abs() > O3D_ERRBOUND_A * permanent {
+        return det;
+    }
+    orient3d_exact(a, b, c, d).estimate()
+}
+
+fn orient3d_exact(
+    a: &Vector3<f64>,
+    b: &Vector3<f64>,
+    c: &Vector3<f64>,
+    d: &Vector3<f64>,
+) -> Expansion<96> {
+    let p = [a, b, c, d];
+    lifted_det4(p.map(|p| (p.x, p.y)), &p.map(|p| Expansion::new(p.z)))
+}
+
+/// Positive if `d` is inside the circle through `a`, `b` and `c`, negative if outside and
+/// zero if the points are cocircular, provided `a`, `b` and `c` are counter-clockwise.
+/// The sign is reversed if they are clockwise.
+pub fn incircle(a: &Vector2<f64>, b: &Vector2<f64>, c: &Vector2<f64>, d: &Vector2<f64>) -> f64 {
+    let (ad, bd, cd) = (*a - *d, *b - *d, *c - *d);
+    let (bdxcdy, cdxbdy) = (bd.x * cd.y, cd.x * bd.y);
+    let (cdxady, adxcdy) = (cd.x * ad.y, ad.x * cd.y);
+    let (adxbdy, bdxady) = (ad.x * bd.y, bd.x * ad.y);
+    let alift = ad.x * ad.x + ad.y * ad.y;
+    let blift = bd.x * bd.x + bd.y * bd.y;
+    let clift = cd.x * cd.x + cd.y * cd.y;
+    let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+        + (cdxady.abs() + adxcdy.abs()) * blift
+        + (adxbdy.abs() + bdxady.abs()) * clift;
+    if det.abs() > ICC_ERRBOUND_A * permanent {
+        return det;
+    }
+    incircle_adapt(a, b, c, d, permanent)
+}
+
+// The exact determinant of the rounded differences, which is the exact result when the
+// differences did not round.
+#[inline(never)]
+fn incircle_adapt(
+    a: &Vector2<f64>,
+    b: &Vector2<f64>,
+    c: &Vector2<f64>,
+    d: &Vector2<f64>,
+    permanent: f64,
+) -> f64 {
+    let (ad, bd, cd) = (*a - *d, *b - *d, *c - *d);
+    let lifted = |e: &Expansion<4>, v: &Vector2<f64>| -> Expansion<32> {
+        Expansion::sum(
+            &Expansion::<16>::scale(&Expansion::<8>::scale(e, v.x), v.x),
+            &Expansion::<16>::scale(&Expansion::<8>::scale(e, v.y), v.y),
+        )
+    };
+    let det = Expansion::<96>::sum(
+        &Expansion::<64>::sum(
+            &lifted(&cross2(&bd, &cd), &ad),
+            &lifted(&cross2(&cd, &ad), &bd),
+        ),
+        &lifted(&cross2(&ad, &bd), &cd),
+    )
+    .estimate();
+    if det.abs() >= ICC_ERRBOUND_B * permanent {
+        return det;
+    }
+    let exact = [(a, d), (b, d), (c, d)]
+        .iter()
+        .all(|(p, d)| diff_tail(p.x, d.x) == 0.0 && diff_tail(p.y, d.y) == 0.0);
+    if exact {
+        return det;
+    }
+    incircle_exact(a, b, c, d).estimate()
+}
+
+#[inline(never)]
+fn incircle_exact(
+    a: &Vector2<f64>,
+    b: &Vector2<f64>,
+    c: &Vector2<f64>,
+    d: &Vector2<f64>,
+) -> Expansion<384> {
+    let p = [a, b, c, d];
+    let lift = |p: &Vector2<f64>| {
+        Expansion::sum(
+            &Expansion::<2>::product(p.x, p.x),
+            &Expansion::<2>::product(p.y, p.y),
+        )
+    };
+    lifted_det4(p.map(|p| (p.x, p.y)), &p.map(lift))
+}
+
+/// Positive if `e` is inside the sphere through `a`, `b`, `c` and `d`, negative if outside
+/// and zero if the points are cospherical, provided `orient3d(a, b, c, d)` is positive.
+/// The sign is reversed if it is negative.
+pub fn insphere(
+    a: &Vector3<f64>,
+    b: &Vector3<f64>,
+    c: &Vector3<f64>,
+    d: &Vector3<f64>,
+    e: &Vector3<f64>,
+) -> f64 {
+    let (ae, be, ce, de) = (*a - *e, *b - *e, *c - *e, *d - *e);
+    let (aexbey, bexaey) = (ae.x * be.y, be.x * ae.y);
+    let (bexcey, cexbey) = (be.x * ce.y, ce.x * be.y);
+    let (cexdey, dexcey) = (ce.x * de.y, de.x * ce.y);
+    let (dexaey, aexdey) = (de.x * ae.y, ae.x * de.y);
+    let (aexcey, cexaey) = (ae.x * ce.y, ce.x * ae.y);
+    let (bexdey, dexbey) = (be.x * de.y, de.x * be.y);
+    let (ab, bc, cd, da) = (
+        aexbey - bexaey,
+        bexcey - cexbey,
+        cexdey - dexcey,
+        dexaey - aexdey,
+    );
+    let (ac, bd) = (aexcey - cexaey, bexdey - dexbey);
+    let abc = ae.z * bc - be.z * ac + ce.z * ab;
+    let bcd = be.z * cd - ce.z * bd + de.z * bc;
+    let cda = ce.z * da + de.z * ac + ae.z * cd;
+    let dab = de.z * ab + ae.z * bd + be.z * da;
+    let lift = |v: &Vector3<f64>| Vector3::dot(v, v);
+    let (alift, blift, clift, dlift) = (lift(&ae), lift(&be), lift(&ce), lift(&de));
+    let det = (dlift * abc - clift * dab) + (blift * cda - alift * bcd);
+
+    let (aez, bez, cez, dez) = (ae.z.abs(), be.z.abs(), ce.z.abs(), de.z.abs());
+    let ab = aexbey.abs() + bexaey.abs();
+    let bc = bexcey.abs() + cexbey.abs();
+    let cd = cexdey.abs() + dexcey.abs();
+    let da = dexaey.abs() + aexdey.abs();
+    let ac = aexcey.abs() + cexaey.abs();
+    let bd = bexdey.abs() + dexbey.abs();
+    let permanent = (cd * bez + bd * cez + bc * dez) * alift
+        + (da * cez + ac * dez + cd * aez) * blift
+        + (ab * dez + bd * aez + da * bez) * clift
+        + (bc * aez + ac * bez + ab * cez) * dlift;
+    if det.abs() > ISP_ERRBOUND_A * permanent {
+        return det;
+    }
+    insphere_adapt(a, b, c, d, e, permanent)
+}
+
+// As `incircle_adapt`, with the cofactors of the lifted column built from 2x2 minors.
+#[inline(never)]
+fn insphere_adapt(
+    a: &Vector3<f64>,
+    b: &Vector3<f64>,
+    c: &Vector3<f64>,
+    d: &Vector3<f64>,
+    e: &Vector3<f64>,
+    permanent: f64,
+) -> f64 {
+    let (ae, be, ce, de) = (*a - *e, *b - *e, *c - *e, *d - *e);
+    let xy = |v: &Vector3<f64>| Vector2::new(v.x, v.y);
+    let (ab, bc, cd) = (
+        cross2(&xy(&ae), &xy(&be)),
+        cross2(&xy(&be), &xy(&ce)),
+        cross2(&xy(&ce), &xy(&de)),
+    );
+    let (da, ac, bd) = (
+        cross2(&xy(&de), &xy(&ae)),
+        cross2(&xy(&ae), &xy(&ce)),
+        cross2(&xy(&be), &xy(&de)),
+    );
+    // `p.z * x + q.z * y + r.z * z`.
+    let cofactor =
+        |x: &Expansion<4>, p: f64, y: &Expansion<4>, q: f64, z: &Expansion<4>, r: f64| {
+            Expansion::<24>::sum(
+                &Expansion::<16>::sum(&Expansion::<8>::scale(x, p), &Expansion::<8>::scale(y, q)),
+                &Expansion::<8>::scale(z, r),
+            )
+        };
+    let abc = cofactor(&bc, ae.z, &ac, -be.z, &ab, ce.z);
+    let bcd = cofactor(&cd, be.z, &bd, -ce.z, &bc, de.z);
+    let cda = cofactor(&da, ce.z, &ac, de.z, &cd, ae.z);
+    let dab = cofactor(&ab, de.z, &bd, ae.z, &da, be.z);
+    let lifted = |e: &Expansion<24>, v: &Vector3<f64>| -> Expansion<288> {
+        let square = |x: f64| Expansion::<96>::scale(&Expansion::<48>::scale(e, x), x);
+        Expansion::sum(
+            &Expansion::<192>::sum(&square(v.x), &square(v.y)),
+            &square(v.z),
+        )
+    };
+    let det = Expansion::<1152>::sum(
+        &Expansion::<576>::sum(&lifted(&abc, &de), &lifted(&dab, &ce).negate()),
+        &Expansion::<576>::sum(&lifted(&cda, &be), &lifted(&bcd, &ae).negate()),
+    )
+    .estimate();
+    if det.abs() >= ISP_ERRBOUND_B * permanent {
+        return det;
+    }
+    let exact = [(a, e), (b, e), (c, e), (d, e)].iter().all(|(p, e)| {
+        diff_tail(p.x, e.x) == 0.0 && diff_tail(p.y, e.y) == 0.0 && diff_tail(p.z, e.z) == 0.0
+    });
+    if exact {
+        return det;
+    }
+    insphere_exact(a, b, c, d, e).estimate()
+}
+
+// Laplace expansion of the 5x5 determinant with rows `(x, y, z, x² + y² + z², 1)` along its
+// lifted column. The expansions take about 60 KiB of stack, hence the adaptive stage.
+#[inline(never)]
+fn insphere_exact(
+    a: &Vector3<f64>,
+    b: &Vector3<f64>,
+    c: &Vector3<f64>,
+    d: &Vector3<f64>,
+    e: &Vector3<f64>,
+) -> Expansion<5760> {
+    let p = [a, b, c, d, e];
+    (0..5).fold(Expansion::new(0.0), |det, i| {
+        let [q, r, s, t] = [0, 1, 2, 3].map(|k| p[if k < i { k } else { k + 1 }]);
+        let lift: Expansion<6> = Expansion::sum(
+            &Expansion::<4>::sum(
+                &Expansion::<2>::product(p[i].x, p[i].x),
+                &Expansion::<2>::product(p[i].y, p[i].y),
+            ),
+            &Expansion::<2>::product(p[i].z, p[i].z),
+        );
+        let term: Expansion<1152> = Expansion::mul(&lift, &orient3d_exact(q, r, s, t));
+        // The sign of the cofactor of row `i` in the fourth column.
+        if i % 2 == 0 {
+            Expansion::sum(&det, &term.negate())
+        } else {
+            Expansion::sum(&det, &term)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    fn sign(x: f64) -> i64 {
+        if x > 0.0 {
+            1
+        } else if x < 0.0 {
+            -1
+        } else {
+            0
+        }
+    }
+
+    #[test]
+    fn predicates_orient2d() {
+        let a = Vector2::new(0.0, 0.0);
+        let b = Vector2::new(1.0, 0.0);
+        assert_eq!(orient2d(&a, &b, &Vector2::new(0.0, 1.0)), 1.0);
+        assert_eq!(orient2d(&a, &b, &Vector2::new(0.0, -1.0)), -1.0);
+        assert_eq!(orient2d(&a, &b, &Vector2::new(7.0, 0.0)), 0.0);
+
+        // Points on the line y = x near 0.5, perturbed by single ulps: the naive
+        // determinant gets many of these signs wrong.
+        let (p, q) = (Vector2::new(12.0, 12.0), Vector2::new(24.0, 24.0));
+        let ulp = f64::EPSILON * 0.5;
+        for i in 0..64 {
+            for j in 0..64 {
+                let r = Vector2::new(0.5 + i as f64 * ulp, 0.5 + j as f64 * ulp);
+                let expected = (j as i64 - i as i64).signum();
+                assert_eq!(sign(orient2d(&r, &p, &q)), expected);
+                assert_eq!(sign(orient2d(&p, &q, &r)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn predicates_orient3d() {
+        let (a, b, c) = (
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+        assert_eq!(orient3d(&a, &b, &c, &Vector3::new(0.0, 0.0, 1.0)), -1.0);
+        assert_eq!(orient3d(&a, &b, &c, &Vector3::new(3.0, 4.0, -1.0)), 1.0);
+        assert_eq!(orient3d(&a, &b, &c, &Vector3::new(3.0, 4.0, 0.0)), 0.0);
+
+        // Points within a few ulps of the tilted plane z = x + y, which the triangle spans
+        // at a distance: dx + dy - dz is (i + j - 2k) ulps of 0.5.
+        let (a, b, c) = (
+            Vector3::new(12.0, 12.0, 24.0),
+            Vector3::new(24.0, -6.0, 18.0),
+            Vector3::new(-8.0, 20.0, 12.0),
+        );
+        let n = Vector3::cross(&(b - a), &(c - a));
+        assert!(n.x == n.y && n.x == -n.z && n.x != 0.0);
+        let ulp = f64::EPSILON * 0.5;
+        for i in -8i64..=8 {
+            for j in -8i64..=8 {
+                for k in -8i64..=8 {
+                    let d = Vector3::new(
+                        0.5 + i as f64 * ulp,
+                        0.5 + j as f64 * ulp,
+                        1.0 + 2.0 * k as f64 * ulp,
+                    );
+                    let expected = -sign(n.x) * (i + j - 2 * k).signum();
+                    assert_eq!(sign(orient3d(&a, &b, &c, &d)), expected);
+                    assert_eq!(sign(orient3d(&d, &a, &b, &c)), -expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn predicates_incircle() {
+        // Counter-clockwise on the circle of radius 5, which also passes through (3, 4).
+        let (a, b, c) = (
+            Vector2::new(5.0, 0.0),
+            Vector2::new(0.0, 5.0),
+            Vector2::new(-5.0, 0.0),
+        );
+        assert!(incircle(&a, &b, &c, &Vector2::new(0.0, 0.0)) > 0.0);
+        assert!(incircle(&a, &c, &b, &Vector2::new(0.0, 0.0)) < 0.0);
+        assert!(incircle(&a, &b, &c, &Vector2::new(5.0, 5.0)) < 0.0);
+
+        // Around (3, 4): |d|² - 25 is 6 dx + 8 dy plus a positive second order term.
+        for i in -8i64..=8 {
+            for j in -8i64..=8 {
+                let dx = i as f64 * f64::EPSILON * 2.0;
+                let dy = j as f64 * f64::EPSILON * 4.0;
+                let d = Vector2::new(3.0 + dx, 4.0 + dy);
+                let linear = 6 * i + 16 * j;
+                let expected = if linear != 0 {
+                    -linear.signum()
+                } else if i != 0 || j != 0 {
+                    -1
+                } else {
+                    0
+                };
+                assert_eq!(sign(incircle(&a, &b, &c, &d)), expected);
+                assert_eq!(sign(incircle(&d, &a, &b, &c)), -expected);
+            }
+        }
+    }
+
+    #[test]
+    fn predicates_insphere() {
+        // Positively oriented on the sphere of radius 3, which also passes through (1, 2, 2).
+        let (a, b, c, d) = (
+            Vector3::new(3.0, 0.0, 0.0),
+            Vector3::new(0.0, 3.0, 0.0),
+            Vector3::new(0.0, 0.0, 3.0),
+            Vector3::new(-3.0, 0.0, 0.0),
+        );
+        let (a, b) = if orient3d(&a, &b, &c, &d) > 0.0 {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        assert!(insphere(&a, &b, &c, &d, &Vector3::new(0.0, 0.0, 0.0)) > 0.0);
+        assert!(insphere(&b, &a, &c, &d, &Vector3::new(0.0, 0.0, 0.0)) < 0.0);
+        assert!(insphere(&a, &b, &c, &d, &Vector3::new(3.0, 3.0, 0.0)) < 0.0);
+
+        // Around (1, 2, 2): |e|² - 9 is 2 dx + 4 dy + 4 dz plus a positive second order term.
+        for i in -4i64..=4 {
+            for j in -4i64..=4 {
+                for k in -4i64..=4 {
+                    let e = Vector3::new(
+                        1.0 + i as f64 * f64::EPSILON,
+                        2.0 + j as f64 * f64::EPSILON * 2.0,
+                        2.0 + k as f64 * f64::EPSILON * 2.0,
+                    );
+                    let linear = 2 * i + 8 * j + 8 * k;
+                    let expected = if linear != 0 {
+                        -linear.signum()
+                    } else if i != 0 || j != 0 || k != 0 {
+                        -1
+                    } else {
+                        0
+                    };
+                    assert_eq!(sign(insphere(&a, &b, &c, &d, &e)), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn predicates_exact_agrees_with_filter() {
+        let mut rng = SmallRng::seed_from_u64(11);
+        let mut v2 = || Vector2::new(rng.gen::<f64>(), rng.gen::<f64>());
+        for _ in 0..200 {
+            let (a, b, c, d) = (v2(), v2(), v2(), v2());
+            assert_eq!(
+                sign(incircle(&a, &b, &c, &d)),
+                sign(incircle_exact(&a, &b, &c, &d).estimate())
+            );
+        }
+        let mut rng = SmallRng::seed_from_u64(12);
+        let mut v3 = || Vector3::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>());
+        for _ in 0..200 {
+            let (a, b, c, d, e) = (v3(), v3(), v3(), v3(), v3());
+            assert_eq!(
+                sign(orient3d(&a, &b, &c, &d)),
+                sign(orient3d_exact(&a, &b, &c, &d).estimate())
+            );
+            assert_eq!(
+                sign(insphere(&a, &b, &c, &d, &e)),
+                sign(insphere_exact(&a, &b, &c, &d, &e).estimate())
+            );
+        }
+
+        // On a coarse grid the differences are exact, so the adaptive stage decides alone,
+        // including the many cocircular and cospherical cases.
+        let mut rng = SmallRng::seed_from_u64(13);
+        let mut g = || rng.gen_range(0..8) as f64 * 0.25;
+        for _ in 0..500 {
+            let (a, b, c, d) = (
+                Vector2::new(g(), g()),
+                Vector2::new(g(), g()),
+                Vector2::new(g(), g()),
+                Vector2::new(g(), g()),
+            );
+            assert_eq!(
+                sign(incircle_adapt(&a, &b, &c, &d, 0.0)),
+                sign(incircle_exact(&a, &b, &c, &d).estimate())
+            );
+            let p = [a, b, c, d, Vector2::new(g(), g()), Vector2::new(g(), g())];
+            let [a, b, c, d, e] = [0, 1, 2, 3, 4].map(|k| Vector3::new(p[k].x, p[k].y, p[k + 1].x));
+            assert_eq!(
+                sign(insphere_adapt(&a, &b, &c, &d, &e, 0.0)),
+                sign(insphere_exact(&a, &b, &c, &d, &e).estimate())
+            );
+        }
+    }
+}
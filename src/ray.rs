@@ -0,0 +1,383 @@
+use crate::aabb::Aabb3;
+use crate::matrix::Matrix4;
+use crate::scalar::Real;
+use crate::vector::*;
+
+/// Half-line `origin + t * direction` for `t >= 0`. The direction need not be normalized,
+/// hit distances are then in units of its length.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray3<T> {
+    pub origin: Vector3<T>,
+    pub direction: Vector3<T>,
+}
+
+/// Intersection of a ray with a surface, at `origin + t * direction` with the unit `normal`
+/// of the surface there.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RayHit<T> {
+    pub t: T,
+    pub normal: Vector3<T>,
+}
+
+/// Intersection of a ray with a triangle `a, b, c`. `barycentric` holds the weights of
+/// `a`, `b` and `c`, `normal` is on the side of counter-clockwise winding.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TriangleHit<T> {
+    pub t: T,
+    pub normal: Vector3<T>,
+    pub barycentric: Vector3<T>,
+}
+
+#[inline]
+fn component<T: Copy>(v: &Vector3<T>, i: usize) -> T {
+    [v.x, v.y, v.z][i]
+}
+
+impl<T> Ray3<T> {
+    #[inline]
+    pub fn new(origin: Vector3<T>, direction: Vector3<T>) -> Ray3<T> {
+        Ray3 { origin, direction }
+    }
+}
+
+impl<T: Real> Ray3<T> {
+    #[inline]
+    pub fn at(r: &Ray3<T>, t: T) -> Vector3<T> {
+        r.origin + r.direction * t
+    }
+
+    /// The ray transformed by the affine `m`. The direction is not renormalized, so hit
+    /// distances carry over between the two spaces.
+    #[inline]
+    pub fn transform(m: &Matrix4<T>, r: &Ray3<T>) -> Ray3<T> {
+        Ray3::new(
+            Matrix4::transform_point(m, &r.origin),
+            Matrix4::transform_vector(m, &r.direction),
+        )
+    }
+
+    /// Nearest hit with the sphere, the exit point if the origin is inside.
+    pub fn intersect_sphere(r: &Ray3<T>, center: &Vector3<T>, radius: T) -> Option<RayHit<T>> {
+        let oc = r.origin - *center;
+        let a = Vector3::dot(&r.direction, &r.direction);
+        let b = Vector3::dot(&oc, &r.direction);
+        let c = Vector3::dot(&oc, &oc) - radius * radius;
+        let disc = b * b - a * c;
+        if disc < T::zero() || a == T::zero() {
+            return None;
+        }
+        let s = disc.sqrt();
+        let mut t = (-b - s) / a;
+        if t < T::zero() {
+            t = (-b + s) / a;
+            if t < T::zero() {
+                return None;
+            }
+        }
+        let normal = (Ray3::at(r, t) - *center) * radius.recip();
+        Some(RayHit { t, normal })
+    }
+
+    /// Hit with the plane of points `p` where `dot(normal, p) + d = 0`, from either side.
+    /// The hit normal is `normal` normalized.
+    pub fn intersect_plane(r: &Ray3<T>, normal: &Vector3<T>, d: T) -> Option<RayHit<T>> {
+        let denom = Vector3::dot(normal, &r.direction);
+        if denom == T::zero() {
+            return None;
+        }
+        let t = -(Vector3::dot(normal, &r.origin) + d) / denom;
+        if t < T::zero() {
+            return None;
+        }
+        Some(RayHit {
+            t,
+            normal: Vector3::normalize(normal),
+        })
+    }
+
+    /// Möller–Trumbore ray–triangle test, hitting both faces. Rays parallel to the triangle
+    /// miss it.
+    pub fn intersect_triangle(
+        r: &Ray3<T>,
+        a: &Vector3<T>,
+        b: &Vector3<T>,
+        c: &Vector3<T>,
+    ) -> Option<TriangleHit<T>> {
+        let e1 = *b - *a;
+        let e2 = *c - *a;
+        let p = Vector3::cross(&r.direction, &e2);
+        let det = Vector3::dot(&e1, &p);
+        if det == T::zero() {
+            return None;
+        }
+        let inv_det = det.recip();
+        let s = r.origin - *a;
+        let u = Vector3::dot(&s, &p) * inv_det;
+        if u < T::zero() || u > T::one() {
+            return None;
+        }
+        let q = Vector3::cross(&s, &e1);
+        let v = Vector3::dot(&r.direction, &q) * inv_det;
+        if v < T::zero() || u + v > T::one() {
+            return None;
+        }
+        let t = Vector3::dot(&e2, &q) * inv_det;
+        if t < T::zero() {
+            return None;
+        }
+        Some(TriangleHit {
+            t,
+            normal: Vector3::normalize(&Vector3::cross(&e1, &e2)),
+            barycentric: Vector3::new(T::one() - u - v, u, v),
+        })
+    }
+
+    /// Woop, Benthin and Wald's watertight ray–triangle test: rays through a shared edge or
+    /// vertex of a closed mesh hit at least one of its triangles.
+    pub fn intersect_triangle_watertight(
+        r: &Ray3<T>,
+        a: &Vector3<T>,
+        b: &Vector3<T>,
+        c: &Vector3<T>,
+    ) -> Option<TriangleHit<T>> {
+        // Permute the axes so the direction is largest along z, keeping the winding.
+        let d = r.direction;
+        let kz = if d.x.abs() > d.y.abs() {
+            if d.x.abs() > d.z.abs() {
+                0
+            } else {
+                2
+            }
+        } else if d.y.abs() > d.z.abs() {
+            1
+        } else {
+            2
+        };
+        let (mut kx, mut ky) = ((kz + 1) % 3, (kz + 2) % 3);
+        let dz = component(&d, kz);
+        if dz == T::zero() {
+            return None;
+        }
+        if dz < T::zero() {
+            core::mem::swap(&mut kx, &mut ky);
+        }
+
+        // Shear so the ray points along +z from the origin.
+        let sx = component(&d, kx) / dz;
+        let sy = component(&d, ky) / dz;
+        let sz = dz.recip();
+        let shear = |p: &Vector3<T>| {
+            let p = *p - r.origin;
+            let z = component(&p, kz);
+            Vector3::new(
+                component(&p, kx) - sx * z,
+                component(&p, ky) - sy * z,
+                sz * z,
+            )
+        };
+        let (sa, sb, sc) = (shear(a), shear(b), shear(c));
+
+        // Scaled barycentrics as 2D edge functions, on an edge exactly zero.
+        let u = sc.x * sb.y - sc.y * sb.x;
+        let v = sa.x * sc.y - sa.y * sc.x;
+        let w = sb.x * sa.y - sb.y * sa.x;
+        let zero = T::zero();
+        if (u < zero || v < zero || w < zero) && (u > zero || v > zero || w > zero) {
+            return None;
+        }
+        let det = u + v + w;
+        if det == zero {
+            return None;
+        }
+        let t = u * sa.z + v * sb.z + w * sc.z;
+        if (det > zero && t < zero) || (det < zero && t > zero) {
+            return None;
+        }
+        let inv_det = det.recip();
+        Some(TriangleHit {
+            t: t * inv_det,
+            normal: Vector3::normalize(&Vector3::cross(&(*b - *a), &(*c - *a))),
+            barycentric: Vector3::new(u * inv_det, v * inv_det, w * inv_det),
+        })
+    }
+
+    /// Slab test. Returns the entry point, or the exit point if the origin is inside, with
+    /// the outward normal of the face hit.
+    pub fn intersect_aabb(r: &Ray3<T>, b: &Aabb3<T>) -> Option<RayHit<T>> {
+        // (t, axis, sign of the face normal) of the latest entry and earliest exit.
+        let mut near: Option<(T, usize, T)> = None;
+        let mut far: Option<(T, usize, T)> = None;
+        for i in 0..3 {
+            let o = component(&r.origin, i);
+            let d = component(&r.direction, i);
+            let (lo, hi) = (component(&b.min, i), component(&b.max, i));
+            if d == T::zero() {
+                if o < lo || o > hi {
+                    return None;
+                }
+                continue;
+            }
+            let inv = d.recip();
+            let (t0, t1) = ((lo - o) * inv, (hi - o) * inv);
+            let (entry, exit) = if d > T::zero() {
+                ((t0, i, -T::one()), (t1, i, T::one()))
+            } else {
+                ((t1, i, T::one()), (t0, i, -T::one()))
+            };
+            if near.is_none_or(|n| entry.0 > n.0) {
+                near = Some(entry);
+            }
+            if far.is_none_or(|f| exit.0 < f.0) {
+                far = Some(exit);
+            }
+        }
+        let (near, far) = (near?, far?);
+        if near.0 > far.0 || far.0 < T::zero() {
+            return None;
+        }
+        let (t, axis, sign) = if near.0 >= T::zero() { near } else { far };
+        let mut n = [T::zero(); 3];
+        n[axis] = sign;
+        Some(RayHit {
+            t,
+            normal: Vector3::new(n[0], n[1], n[2]),
+        })
+    }
+
+    /// Hit with the box `b` placed in the world by the affine `m`, as for `intersect_aabb`.
+    /// Returns `None` if `m` is singular.
+    pub fn intersect_oriented_box(r: &Ray3<T>, m: &Matrix4<T>, b: &Aabb3<T>) -> Option<RayHit<T>> {
+        let inv = Matrix4::inverse(m)?;
+        let hit = Ray3::intersect_aabb(&Ray3::transform(&inv, r), b)?;
+        let normal = Matrix4::transform_vector(&Matrix4::transpose(&inv), &hit.normal);
+        Some(RayHit {
+            t: hit.t,
+            normal: Vector3::normalize(&normal),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn ray_sphere_and_plane() {
+        let r = Ray3::new(Vector3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 2.0));
+        let c = Vector3::new(0.0, 0.0, 0.0);
+        let hit = Ray3::intersect_sphere(&r, &c, 1.0).unwrap();
+        assert_relative_eq!(hit.t, 2.0);
+        assert_relative_eq!(hit.normal, Vector3::new(0.0, 0.0, -1.0));
+        let inside = Ray3::new(c, Vector3::new(1.0, 0.0, 0.0));
+        let hit = Ray3::intersect_sphere(&inside, &c, 1.0).unwrap();
+        assert_relative_eq!(Ray3::at(&inside, hit.t), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(
+            Ray3::intersect_sphere(&r, &Vector3::new(3.0, 0.0, 0.0), 1.0),
+            None
+        );
+
+        let hit = Ray3::intersect_plane(&r, &Vector3::new(0.0, 0.0, 2.0), -2.0).unwrap();
+        assert_relative_eq!(hit.t, 3.0);
+        assert_relative_eq!(hit.normal, Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            Ray3::intersect_plane(&r, &Vector3::new(0.0, 0.0, 1.0), 6.0),
+            None
+        );
+    }
+
+    #[test]
+    fn ray_triangle() {
+        let (a, b, c) = (
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+        let r = Ray3::new(Vector3::new(0.25, 0.5, 1.0), Vector3::new(0.0, 0.0, -1.0));
+        let expected = TriangleHit {
+            t: 1.0,
+            normal: Vector3::new(0.0, 0.0, 1.0),
+            barycentric: Vector3::new(0.25, 0.25, 0.5),
+        };
+        for hit in [
+            Ray3::intersect_triangle(&r, &a, &b, &c),
+            Ray3::intersect_triangle_watertight(&r, &a, &b, &c),
+            // Back faces are hit too.
+            Ray3::intersect_triangle(&r, &a, &c, &b).map(|h| TriangleHit {
+                normal: h.normal * -1.0,
+                barycentric: Vector3::new(h.barycentric.x, h.barycentric.z, h.barycentric.y),
+                ..h
+            }),
+        ] {
+            let hit = hit.unwrap();
+            assert_relative_eq!(hit.t, expected.t);
+            assert_relative_eq!(hit.normal, expected.normal);
+            assert_relative_eq!(hit.barycentric, expected.barycentric);
+        }
+        let miss = Ray3::new(Vector3::new(0.75, 0.5, 1.0), Vector3::new(0.0, 0.0, -1.0));
+        assert!(Ray3::intersect_triangle(&miss, &a, &b, &c).is_none());
+        assert!(Ray3::intersect_triangle_watertight(&miss, &a, &b, &c).is_none());
+    }
+
+    #[test]
+    fn ray_triangle_watertight_shared_edge() {
+        // A ray through the diagonal shared by two triangles of a quad hits one of them.
+        let q = [
+            Vector3::new(0.1, 0.3, 0.0),
+            Vector3::new(1.7, 0.2, 0.1),
+            Vector3::new(1.9, 1.3, 0.3),
+            Vector3::new(0.2, 1.1, 0.2),
+        ];
+        for i in 0..16 {
+            let p = q[0] + (q[2] - q[0]) * (i as f64 / 16.0);
+            let r = Ray3::new(
+                p + Vector3::new(0.3, -0.2, 1.0),
+                Vector3::new(-0.3, 0.2, -1.0),
+            );
+            let hits = Ray3::intersect_triangle_watertight(&r, &q[0], &q[1], &q[2]).is_some()
+                || Ray3::intersect_triangle_watertight(&r, &q[0], &q[2], &q[3]).is_some();
+            assert!(hits, "{}", i);
+        }
+    }
+
+    #[test]
+    fn ray_boxes() {
+        let b = Aabb3::new(Vector3::new(-1.0, -1.0, -1.0), Vector3::new(1.0, 1.0, 1.0));
+        let r = Ray3::new(Vector3::new(-3.0, 0.5, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        let hit = Ray3::intersect_aabb(&r, &b).unwrap();
+        assert_eq!(hit.t, 2.0);
+        assert_eq!(hit.normal, Vector3::new(-1.0, 0.0, 0.0));
+        let inside = Ray3::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, -2.0, 0.0));
+        let hit = Ray3::intersect_aabb(&inside, &b).unwrap();
+        assert_eq!(hit.t, 0.5);
+        assert_eq!(hit.normal, Vector3::new(0.0, -1.0, 0.0));
+        let miss = Ray3::new(Vector3::new(-3.0, 1.5, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(Ray3::intersect_aabb(&miss, &b), None);
+        let behind = Ray3::new(Vector3::new(3.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(Ray3::intersect_aabb(&behind, &b), None);
+
+        // The box rotated a quarter turn around z and moved to x = 10.
+        let m = Matrix4::translate(&Vector3::new(10.0, 0.0, 0.0))
+            * Matrix4::rotate(FRAC_PI_2, &Vector3::new(0.0, 0.0, 1.0))
+            * Matrix4::scale(&Vector3::new(2.0, 1.0, 1.0));
+        let r = Ray3::new(Vector3::new(10.0, -5.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+        let hit = Ray3::intersect_oriented_box(&r, &m, &b).unwrap();
+        assert_relative_eq!(hit.t, 3.0, epsilon = 1e-12);
+        assert_relative_eq!(hit.normal, Vector3::new(0.0, -1.0, 0.0), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn ray_transform() {
+        let r = Ray3::new(Vector3::new(1.0, 2.0, 3.0), Vector3::new(0.0, 1.0, 0.0));
+        let m = Matrix4::translate(&Vector3::new(1.0, 0.0, 0.0))
+            * Matrix4::scale(&Vector3::new(2.0, 2.0, 2.0));
+        let t = Ray3::transform(&m, &r);
+        assert_eq!(t.origin, Vector3::new(3.0, 4.0, 6.0));
+        assert_eq!(t.direction, Vector3::new(0.0, 2.0, 0.0));
+        assert_eq!(
+            Ray3::at(&t, 1.5),
+            Matrix4::transform_point(&m, &Ray3::at(&r, 1.5))
+        );
+    }
+}
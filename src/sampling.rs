@@ -0,0 +1,280 @@
+//! Warping functions mapping uniform samples in `[0, 1)^2` (or `[0, 1)^3`) to geometric
+//! domains, and with the `rand` feature `Distribution` impls built on them.
+//!
+//! Directions on the hemisphere are around `+z`.
+
+use crate::matrix::Matrix4;
+use crate::scalar::Real;
+use crate::vector::*;
+use core::f64::consts::{FRAC_1_PI, FRAC_PI_4, PI};
+
+#[inline]
+fn clamp_zero<T: Real>(v: T) -> T {
+    if v < T::zero() {
+        T::zero()
+    } else {
+        v
+    }
+}
+
+pub fn uniform_sphere<T: Real>(u: &Vector2<T>) -> Vector3<T> {
+    let two = T::one() + T::one();
+    let z = T::one() - two * u.x;
+    let r = clamp_zero(T::one() - z * z).sqrt();
+    let (s, c) = (T::from_f64(2.0 * PI) * u.y).sin_cos();
+    Vector3::new(r * c, r * s, z)
+}
+
+pub fn uniform_sphere_pdf<T: Real>() -> T {
+    T::from_f64(0.25 * FRAC_1_PI)
+}
+
+pub fn uniform_hemisphere<T: Real>(u: &Vector2<T>) -> Vector3<T> {
+    let z = u.x;
+    let r = clamp_zero(T::one() - z * z).sqrt();
+    let (s, c) = (T::from_f64(2.0 * PI) * u.y).sin_cos();
+    Vector3::new(r * c, r * s, z)
+}
+
+pub fn uniform_hemisphere_pdf<T: Real>() -> T {
+    T::from_f64(0.5 * FRAC_1_PI)
+}
+
+/// Shirley and Chiu's concentric mapping from the square to the unit disk.
+pub fn concentric_disk<T: Real>(u: &Vector2<T>) -> Vector2<T> {
+    let two = T::one() + T::one();
+    let x = two * u.x - T::one();
+    let y = two * u.y - T::one();
+    if x == T::zero() && y == T::zero() {
+        return Vector2::new(T::zero(), T::zero());
+    }
+    let (r, theta) = if x.abs() > y.abs() {
+        (x, T::from_f64(FRAC_PI_4) * (y / x))
+    } else {
+        (
+            y,
+            T::from_f64(2.0 * FRAC_PI_4) - T::from_f64(FRAC_PI_4) * (x / y),
+        )
+    };
+    let (s, c) = theta.sin_cos();
+    Vector2::new(r * c, r * s)
+}
+
+/// Malley's method: projects concentric disk samples up to the hemisphere.
+pub fn cosine_hemisphere<T: Real>(u: &Vector2<T>) -> Vector3<T> {
+    let d = concentric_disk(u);
+    let z = clamp_zero(T::one() - Vector2::dot(&d, &d)).sqrt();
+    Vector3::new(d.x, d.y, z)
+}
+
+pub fn cosine_hemisphere_pdf<T: Real>(cos_theta: T) -> T {
+    cos_theta * T::from_f64(FRAC_1_PI)
+}
+
+/// Barycentric coordinates of a uniformly distributed point in a triangle.
+pub fn uniform_triangle<T: Real>(u: &Vector2<T>) -> Vector3<T> {
+    let su = u.x.sqrt();
+    let b0 = T::one() - su;
+    let b1 = u.y * su;
+    Vector3::new(b0, b1, T::one() - b0 - b1)
+}
+
+/// Shoemake's uniformly distributed rotation, from a random unit quaternion.
+pub fn uniform_rotation<T: Real>(u: &Vector3<T>) -> Matrix4<T> {
+    let tau = T::from_f64(2.0 * PI);
+    let r1 = clamp_zero(T::one() - u.x).sqrt();
+    let r2 = u.x.sqrt();
+    let (s1, c1) = (tau * u.y).sin_cos();
+    let (s2, c2) = (tau * u.z).sin_cos();
+    let (x, y, z, w) = (r1 * s1, r1 * c1, r2 * s2, r2 * c2);
+
+    let two = T::one() + T::one();
+    let zero = T::zero();
+    Matrix4::new(
+        Vector4::new(
+            T::one() - two * (y * y + z * z),
+            two * (x * y + w * z),
+            two * (x * z - w * y),
+            zero,
+        ),
+        Vector4::new(
+            two * (x * y - w * z),
+            T::one() - two * (x * x + z * z),
+            two * (y * z + w * x),
+            zero,
+        ),
+        Vector4::new(
+            two * (x * z + w * y),
+            two * (y * z - w * x),
+            T::one() - two * (x * x + y * y),
+            zero,
+        ),
+        Vector4::new(zero, zero, zero, T::one()),
+    )
+}
+
+#[cfg(feature = "rand")]
+pub use self::distributions::*;
+
+#[cfg(feature = "rand")]
+mod distributions {
+    use super::*;
+    use crate::matrix::*;
+    use rand::distributions::{Distribution, Standard};
+    use rand::Rng;
+
+    macro_rules! impl_standard {
+        ($Type: ident, $Field: ty, $($field: ident),+) => {
+            /// Every component sampled from `Standard`.
+            impl<T> Distribution<$Type<T>> for Standard where Standard: Distribution<T> {
+                #[inline]
+                fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $Type<T> {
+                    $Type { $($field: <Standard as Distribution<$Field>>::sample(self, rng)),+ }
+                }
+            }
+        };
+    }
+
+    impl_standard!(Vector2, T, x, y);
+    impl_standard!(Vector3, T, x, y, z);
+    impl_standard!(Vector4, T, x, y, z, w);
+    impl_standard!(Matrix2, Vector2<T>, x, y);
+    impl_standard!(Matrix3, Vector3<T>, x, y, z);
+    impl_standard!(Matrix4, Vector4<T>, x, y, z, w);
+
+    macro_rules! generate_sampler {
+        ($Sampler: ident, $Output: ident, $Input: ident, $f: ident, $doc: expr) => {
+            #[doc = $doc]
+            #[derive(Copy, Clone, Debug, Default)]
+            pub struct $Sampler;
+
+            impl<T: Real> Distribution<$Output<T>> for $Sampler
+            where
+                Standard: Distribution<T>,
+            {
+                #[inline]
+                fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $Output<T> {
+                    $f(&rng.gen::<$Input<T>>())
+                }
+            }
+        };
+    }
+
+    generate_sampler!(
+        UnitSphere,
+        Vector3,
+        Vector2,
+        uniform_sphere,
+        "Uniform directions."
+    );
+    generate_sampler!(
+        UnitHemisphere,
+        Vector3,
+        Vector2,
+        uniform_hemisphere,
+        "Uniform directions with `z >= 0`."
+    );
+    generate_sampler!(
+        CosineHemisphere,
+        Vector3,
+        Vector2,
+        cosine_hemisphere,
+        "Directions with `z >= 0` and a density proportional to `z`."
+    );
+    generate_sampler!(
+        UnitDisk,
+        Vector2,
+        Vector2,
+        concentric_disk,
+        "Uniform points in the unit disk."
+    );
+    generate_sampler!(
+        UnitTriangle,
+        Vector3,
+        Vector2,
+        uniform_triangle,
+        "Barycentric coordinates of uniform points in a triangle."
+    );
+    generate_sampler!(
+        UniformRotation,
+        Matrix4,
+        Vector3,
+        uniform_rotation,
+        "Uniformly distributed rotation matrices."
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> impl Iterator<Item = Vector2<f64>> {
+        (0..16).flat_map(|i| (0..16).map(move |j| Vector2::new(i as f64 / 16.0, j as f64 / 16.0)))
+    }
+
+    #[test]
+    fn sampling_directions() {
+        for u in grid() {
+            let s = uniform_sphere(&u);
+            assert_relative_eq!(Vector3::length(&s), 1.0, epsilon = 1e-12);
+            let h = uniform_hemisphere(&u);
+            assert_relative_eq!(Vector3::length(&h), 1.0, epsilon = 1e-12);
+            assert!(h.z >= 0.0);
+            let c = cosine_hemisphere(&u);
+            assert_relative_eq!(Vector3::length(&c), 1.0, epsilon = 1e-12);
+            assert!(c.z >= 0.0);
+        }
+    }
+
+    #[test]
+    fn sampling_disk_and_triangle() {
+        for u in grid() {
+            let d = concentric_disk(&u);
+            assert!(Vector2::length(&d) <= 1.0 + 1e-12);
+            let b = uniform_triangle(&u);
+            assert!(b.x >= 0.0 && b.y >= 0.0 && b.z >= -1e-15);
+            assert_relative_eq!(b.x + b.y + b.z, 1.0);
+        }
+        let corner = concentric_disk(&Vector2::new(1.0, 1.0));
+        assert_relative_eq!(corner, Vector2::new(0.5f64.sqrt(), 0.5f64.sqrt()));
+    }
+
+    #[test]
+    fn sampling_rotation() {
+        let r = uniform_rotation(&Vector3::new(0.3, 0.7, 0.1));
+        let rt = Matrix4::transpose(&r);
+        assert_relative_eq!(r * rt, Matrix4::identity(), epsilon = 1e-12);
+        assert_relative_eq!(Matrix4::determinant(&r), 1.0, epsilon = 1e-12);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn sampling_distributions() {
+        use rand::distributions::Distribution;
+        use rand::rngs::SmallRng;
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = SmallRng::seed_from_u64(7);
+        let v: Vector3<f32> = rng.gen();
+        assert!(v.x >= 0.0 && v.x < 1.0);
+
+        let n = 20_000;
+        let mut mean = Vector3::splat(0.0);
+        let mut mean_cos = 0.0;
+        for _ in 0..n {
+            mean += UnitSphere.sample(&mut rng) * (1.0 / n as f64);
+            let c: Vector3<f64> = CosineHemisphere.sample(&mut rng);
+            mean_cos += c.z / n as f64;
+        }
+        assert!(Vector3::length(&mean) < 0.03);
+        // E[cos] under a cosine-weighted density is 2/3.
+        assert!((mean_cos - 2.0 / 3.0).abs() < 0.01);
+
+        let r: Matrix4<f64> = UniformRotation.sample(&mut rng);
+        assert_relative_eq!(
+            r * Matrix4::transpose(&r),
+            Matrix4::identity(),
+            epsilon = 1e-12
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::matrix::Matrix4;
+use crate::simd;
+use crate::vector::Vector4;
+use num_traits::{One, Zero};
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Component type of vectors and matrices.
+///
+/// Provides the ring operations (plus division) needed by the arithmetic operators,
+/// `dot` and `cross`. Implemented for all primitive integer and floating point types.
+pub trait Scalar:
+    Copy
+    + PartialOrd
+    + Zero
+    + One
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    // Hooks letting a scalar type substitute specialized implementations of hot `Matrix4`
+    // operations, see `simd.rs`.
+    #[doc(hidden)]
+    #[inline]
+    fn mul_matrix4_and_matrix4(a: &Matrix4<Self>, b: &Matrix4<Self>) -> Matrix4<Self> {
+        Matrix4::mul_matrix_and_matrix_fallback(a, b)
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn mul_matrix4_and_vector4(a: &Matrix4<Self>, b: &Vector4<Self>) -> Vector4<Self> {
+        Matrix4::mul_matrix_and_vector_fallback(a, b)
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn transpose_matrix4(m: &Matrix4<Self>) -> Matrix4<Self> {
+        Matrix4::transpose_fallback(m)
+    }
+}
+
+/// A `Scalar` that can be negated, required by determinants, inverses and projections.
+pub trait SignedScalar: Scalar + Neg<Output = Self> {
+    #[doc(hidden)]
+    #[inline]
+    fn inverse_matrix4(m: &Matrix4<Self>) -> Option<Matrix4<Self>> {
+        Matrix4::inverse_fallback(m)
+    }
+
+    #[inline]
+    fn abs(self) -> Self {
+        if self < Self::zero() {
+            -self
+        } else {
+            self
+        }
+    }
+}
+
+/// A `SignedScalar` approximating the real numbers, required by lengths, normalization,
+/// rotations and perspective projections.
+pub trait Real: SignedScalar {
+    fn from_f64(v: f64) -> Self;
+
+    fn sqrt(self) -> Self;
+
+    fn sin_cos(self) -> (Self, Self);
+
+    fn tan(self) -> Self;
+
+    #[inline]
+    fn recip(self) -> Self {
+        Self::one() / self
+    }
+}
+
+macro_rules! impl_scalar {
+    ($($T: ty),+) => {
+        $(impl Scalar for $T {})+
+    };
+}
+
+macro_rules! impl_signed_scalar {
+    ($($T: ty),+) => {
+        $(impl SignedScalar for $T {
+            #[inline]
+            fn abs(self) -> Self {
+                <$T>::abs(self)
+            }
+        })+
+    };
+}
+
+// Without `std` the float functions come from `libm`.
+macro_rules! impl_real {
+    ($($T: ident: $sqrt: ident, $sin_cos: ident, $tan: ident);+) => {
+        $(impl Real for $T {
+            #[inline]
+            fn from_f64(v: f64) -> Self {
+                v as $T
+            }
+
+            #[inline]
+            fn sqrt(self) -> Self {
+                #[cfg(feature = "std")]
+                return $T::sqrt(self);
+                #[cfg(not(feature = "std"))]
+                return libm::$sqrt(self);
+            }
+
+            #[inline]
+            fn sin_cos(self) -> (Self, Self) {
+                #[cfg(feature = "std")]
+                return $T::sin_cos(self);
+                #[cfg(not(feature = "std"))]
+                return libm::$sin_cos(self);
+            }
+
+            #[inline]
+            fn tan(self) -> Self {
+                #[cfg(feature = "std")]
+                return $T::tan(self);
+                #[cfg(not(feature = "std"))]
+                return libm::$tan(self);
+            }
+
+            #[inline]
+            fn recip(self) -> Self {
+                $T::recip(self)
+            }
+        })+
+    };
+}
+
+impl_scalar!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f64);
+impl_signed_scalar!(i8, i16, i32, i64, i128, isize, f64);
+impl_real!(f32: sqrtf, sincosf, tanf; f64: sqrt, sincos, tan);
+
+impl Scalar for f32 {
+    #[inline]
+    fn mul_matrix4_and_matrix4(a: &Matrix4<f32>, b: &Matrix4<f32>) -> Matrix4<f32> {
+        simd::mul_matrix4_and_matrix4(a, b)
+    }
+
+    #[inline]
+    fn mul_matrix4_and_vector4(a: &Matrix4<f32>, b: &Vector4<f32>) -> Vector4<f32> {
+        simd::mul_matrix4_and_vector4(a, b)
+    }
+
+    #[inline]
+    fn transpose_matrix4(m: &Matrix4<f32>) -> Matrix4<f32> {
+        simd::transpose_matrix4(m)
+    }
+}
+
+impl SignedScalar for f32 {
+    #[inline]
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+
+    #[inline]
+    fn inverse_matrix4(m: &Matrix4<f32>) -> Option<Matrix4<f32>> {
+        simd::inverse_matrix4(m)
+    }
+}
+
+#[cfg(feature = "half")]
+mod half_impl {
+    use super::*;
+    use half::f16;
+
+    impl Scalar for f16 {}
+
+    impl SignedScalar for f16 {}
+
+    impl Real for f16 {
+        #[inline]
+        fn from_f64(v: f64) -> Self {
+            f16::from_f64(v)
+        }
+
+        #[inline]
+        fn sqrt(self) -> Self {
+            f16::from_f32(Real::sqrt(self.to_f32()))
+        }
+
+        #[inline]
+        fn sin_cos(self) -> (Self, Self) {
+            let (s, c) = Real::sin_cos(self.to_f32());
+            (f16::from_f32(s), f16::from_f32(c))
+        }
+
+        #[inline]
+        fn tan(self) -> Self {
+            f16::from_f32(Real::tan(self.to_f32()))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::matrix::*;
+        use crate::vector::*;
+
+        #[test]
+        fn matrix4_rotate_f16() {
+            let one = f16::ONE;
+            let zero = f16::ZERO;
+            let r = Matrix4::rotate(
+                f16::from_f64(std::f64::consts::FRAC_PI_2),
+                &Vector3::new(zero, zero, one),
+            );
+            let p = r * Vector4::new(one, zero, zero, one);
+            assert!((p.x.to_f32()).abs() < 1e-3);
+            assert!((p.y.to_f32() - 1.0).abs() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,421 @@
+//! Low-discrepancy point sets in `[0, 1)^2` and `[0, 1)^3`, for use with the warping
+//! functions in `sampling`:
+//!
+//! ```ignore
+//! let d = sampling::cosine_hemisphere(&Sobol::new().sample2::<f32>(i));
+//! ```
+//!
+//! Samples are computed with 32 bits of precision, so converting to `T` may round a value
+//! just below one up to one.
+
+use crate::scalar::Real;
+use crate::vector::*;
+
+/// Randomization applied to a Halton or Sobol sequence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Scramble {
+    None,
+    /// The same random permutation of the digits at each position for every point.
+    RandomDigit(u32),
+    /// Nested uniform (Owen) scrambling: the permutation of a digit depends on all more
+    /// significant digits.
+    Owen(u32),
+}
+
+/// A sequence of points in `[0, 1)^2`, indexed from zero.
+pub trait Sequence2 {
+    fn sample2<T: Real>(&self, i: u32) -> Vector2<T>;
+}
+
+// Chris Wellons' lowbias32 integer hash.
+#[inline]
+fn hash(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^= x >> 16;
+    x
+}
+
+#[inline]
+fn hash_combine(seed: u32, v: u32) -> u32 {
+    hash(
+        seed ^ v
+            .wrapping_add(0x9e37_79b9)
+            .wrapping_add(seed << 6)
+            .wrapping_add(seed >> 2),
+    )
+}
+
+#[inline]
+fn to_unit<T: Real>(bits: u32) -> T {
+    T::from_f64(bits as f64 * (1.0 / 4_294_967_296.0))
+}
+
+/// `n x m` cells, one point per cell, at the cell center or jittered within it. Indices past
+/// `n * m` wrap around.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Stratified {
+    pub nx: u32,
+    pub ny: u32,
+    pub jitter: Option<u32>,
+}
+
+impl Stratified {
+    /// # Panics
+    ///
+    /// Panics if `nx` or `ny` is zero.
+    pub fn new(nx: u32, ny: u32) -> Stratified {
+        assert!(nx > 0 && ny > 0, "Stratified needs at least one cell");
+        Stratified {
+            nx,
+            ny,
+            jitter: None,
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `nx` or `ny` is zero.
+    pub fn jittered(nx: u32, ny: u32, seed: u32) -> Stratified {
+        assert!(nx > 0 && ny > 0, "Stratified needs at least one cell");
+        Stratified {
+            nx,
+            ny,
+            jitter: Some(seed),
+        }
+    }
+}
+
+impl Sequence2 for Stratified {
+    fn sample2<T: Real>(&self, i: u32) -> Vector2<T> {
+        // The cell count may not fit in 32 bits.
+        let cell = i as u64 % (self.nx as u64 * self.ny as u64);
+        let (cx, cy) = (cell % self.nx as u64, cell / self.nx as u64);
+        let (jx, jy) = match self.jitter {
+            Some(seed) => {
+                let h = hash_combine(seed, i);
+                (
+                    h as f64 * (1.0 / 4_294_967_296.0),
+                    hash(h) as f64 * (1.0 / 4_294_967_296.0),
+                )
+            }
+            None => (0.5, 0.5),
+        };
+        Vector2::new(
+            T::from_f64((cx as f64 + jx) / self.nx as f64),
+            T::from_f64((cy as f64 + jy) / self.ny as f64),
+        )
+    }
+}
+
+/// Radical inverses in bases 2, 3 and 5.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Halton {
+    pub scramble: Scramble,
+}
+
+impl Halton {
+    pub fn new() -> Halton {
+        Halton {
+            scramble: Scramble::None,
+        }
+    }
+
+    pub fn scrambled(scramble: Scramble) -> Halton {
+        Halton { scramble }
+    }
+
+    fn radical_inverse(&self, dimension: u32, base: u32, mut i: u32) -> u32 {
+        let inv_base = 1.0 / base as f64;
+        let mut weight = inv_base;
+        let mut r = 0.0;
+        let mut state = hash_combine(dimension, 0);
+        // Scrambled sequences permute the implicit zero digits too, so they continue down
+        // to 32 bits of precision.
+        while weight * 4_294_967_296.0 >= 1.0 && (i != 0 || self.scramble != Scramble::None) {
+            let digit = i % base;
+            i /= base;
+            let shift = match self.scramble {
+                Scramble::None => 0,
+                Scramble::RandomDigit(seed) | Scramble::Owen(seed) => hash_combine(seed, state),
+            };
+            state = match self.scramble {
+                Scramble::Owen(_) => hash_combine(state, digit),
+                _ => hash_combine(state, base),
+            };
+            r += ((digit + shift % base) % base) as f64 * weight;
+            weight *= inv_base;
+        }
+        // Rounded to nearest, the cast saturates.
+        (r * 4_294_967_296.0 + 0.5) as u32
+    }
+
+    pub fn sample3<T: Real>(&self, i: u32) -> Vector3<T> {
+        Vector3::new(
+            to_unit(self.radical_inverse(0, 2, i)),
+            to_unit(self.radical_inverse(1, 3, i)),
+            to_unit(self.radical_inverse(2, 5, i)),
+        )
+    }
+}
+
+impl Default for Halton {
+    fn default() -> Halton {
+        Halton::new()
+    }
+}
+
+impl Sequence2 for Halton {
+    fn sample2<T: Real>(&self, i: u32) -> Vector2<T> {
+        Vector2::new(
+            to_unit(self.radical_inverse(0, 2, i)),
+            to_unit(self.radical_inverse(1, 3, i)),
+        )
+    }
+}
+
+// Direction numbers of a Sobol dimension with primitive polynomial degree `s`, coefficients
+// `a` and initial numbers `m`.
+const fn direction_numbers(s: usize, a: u32, m: [u32; 2]) -> [u32; 32] {
+    let mut v = [0; 32];
+    let mut k = 0;
+    while k < 32 {
+        v[k] = if k < s {
+            m[k] << (31 - k)
+        } else {
+            let mut x = v[k - s] ^ (v[k - s] >> s);
+            let mut j = 1;
+            while j < s {
+                if (a >> (s - 1 - j)) & 1 != 0 {
+                    x ^= v[k - j];
+                }
+                j += 1;
+            }
+            x
+        };
+        k += 1;
+    }
+    v
+}
+
+const SOBOL_DIRECTIONS: [[u32; 32]; 2] = [
+    direction_numbers(1, 0, [1, 0]),
+    direction_numbers(2, 1, [1, 3]),
+];
+
+/// The first three dimensions of the Sobol sequence (Joe and Kuo's direction numbers).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Sobol {
+    pub scramble: Scramble,
+}
+
+impl Sobol {
+    pub fn new() -> Sobol {
+        Sobol {
+            scramble: Scramble::None,
+        }
+    }
+
+    pub fn scrambled(scramble: Scramble) -> Sobol {
+        Sobol { scramble }
+    }
+
+    fn component(&self, dimension: u32, mut i: u32) -> u32 {
+        let mut r = 0;
+        if dimension == 0 {
+            r = i.reverse_bits();
+        } else {
+            let v = &SOBOL_DIRECTIONS[dimension as usize - 1];
+            let mut k = 0;
+            while i != 0 {
+                if i & 1 != 0 {
+                    r ^= v[k];
+                }
+                i >>= 1;
+                k += 1;
+            }
+        }
+        match self.scramble {
+            Scramble::None => r,
+            Scramble::RandomDigit(seed) => r ^ hash_combine(seed, dimension),
+            Scramble::Owen(seed) => {
+                // Laine-Karras style permutation with Vegdahl's improved constants, applied to
+                // the reversed bits so that each bit is flipped depending on the more significant ones.
+                let seed = hash_combine(seed, dimension);
+                let mut x = r.reverse_bits();
+                x ^= x.wrapping_mul(0x3d20_adea);
+                x = x.wrapping_add(seed);
+                x = x.wrapping_mul((seed >> 16) | 1);
+                x ^= x.wrapping_mul(0x0552_6c56);
+                x ^= x.wrapping_mul(0x53a2_2864);
+                x.reverse_bits()
+            }
+        }
+    }
+
+    pub fn sample3<T: Real>(&self, i: u32) -> Vector3<T> {
+        Vector3::new(
+            to_unit(self.component(0, i)),
+            to_unit(self.component(1, i)),
+            to_unit(self.component(2, i)),
+        )
+    }
+}
+
+impl Default for Sobol {
+    fn default() -> Sobol {
+        Sobol::new()
+    }
+}
+
+impl Sequence2 for Sobol {
+    fn sample2<T: Real>(&self, i: u32) -> Vector2<T> {
+        Vector2::new(to_unit(self.component(0, i)), to_unit(self.component(1, i)))
+    }
+}
+
+/// Roberts' R2 sequence, optionally shifted by a random offset (Cranley-Patterson rotation).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct R2 {
+    pub seed: Option<u32>,
+}
+
+impl R2 {
+    pub fn new() -> R2 {
+        R2 { seed: None }
+    }
+
+    pub fn shifted(seed: u32) -> R2 {
+        R2 { seed: Some(seed) }
+    }
+}
+
+impl Default for R2 {
+    fn default() -> R2 {
+        R2::new()
+    }
+}
+
+impl Sequence2 for R2 {
+    fn sample2<T: Real>(&self, i: u32) -> Vector2<T> {
+        // 2^32 / g and 2^32 / g^2 for the plastic number g, so that the sequence is computed
+        // exactly modulo one.
+        const A1: u32 = 3_242_174_889;
+        const A2: u32 = 2_447_445_414;
+        let (ox, oy) = match self.seed {
+            Some(seed) => (hash_combine(seed, 0), hash_combine(seed, 1)),
+            None => (1 << 31, 1 << 31),
+        };
+        Vector2::new(
+            to_unit(ox.wrapping_add(A1.wrapping_mul(i))),
+            to_unit(oy.wrapping_add(A2.wrapping_mul(i))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Whether the first `2^k` points have exactly one point in every elementary interval of
+    // volume `2^-k`.
+    fn is_net<S: Sequence2>(s: &S, k: u32) -> bool {
+        let n = 1u32 << k;
+        (0..=k).all(|j| {
+            let (nx, ny) = (1u32 << j, 1u32 << (k - j));
+            let mut counts = std::vec![0; n as usize];
+            for i in 0..n {
+                let p: Vector2<f64> = s.sample2(i);
+                let cell = (p.x * nx as f64) as u32 + nx * (p.y * ny as f64) as u32;
+                counts[cell as usize] += 1;
+            }
+            counts.iter().all(|&c| c == 1)
+        })
+    }
+
+    #[test]
+    fn sobol_points() {
+        let s = Sobol::new();
+        assert_eq!(s.sample3::<f64>(0), Vector3::new(0.0, 0.0, 0.0));
+        assert_eq!(s.sample3::<f64>(1), Vector3::new(0.5, 0.5, 0.5));
+        assert_eq!(s.sample3::<f64>(2), Vector3::new(0.25, 0.75, 0.75));
+        assert_eq!(s.sample3::<f64>(3), Vector3::new(0.75, 0.25, 0.25));
+    }
+
+    #[test]
+    fn sobol_net_property() {
+        assert!(is_net(&Sobol::new(), 6));
+        assert!(is_net(&Sobol::scrambled(Scramble::RandomDigit(3)), 6));
+        assert!(is_net(&Sobol::scrambled(Scramble::Owen(3)), 6));
+        assert_ne!(
+            Sobol::scrambled(Scramble::Owen(3)).sample2::<f64>(5),
+            Sobol::new().sample2::<f64>(5)
+        );
+    }
+
+    #[test]
+    fn halton_points() {
+        let h = Halton::new();
+        let p: Vector2<f64> = h.sample2(2);
+        assert_eq!(p.x, 0.25);
+        assert!((p.y - 2.0 / 3.0).abs() < 1e-9);
+        for scramble in &[Scramble::None, Scramble::RandomDigit(9), Scramble::Owen(9)] {
+            // The first 6 points fall into distinct cells of a 2 x 3 grid. Unscrambled points
+            // lie on the cell borders, 1/3 is rounded down to 32 bits.
+            let h = Halton::scrambled(*scramble);
+            let mut seen = [false; 6];
+            for i in 0..6 {
+                let p: Vector2<f64> = h.sample2(i);
+                let cell = (p.x * 2.0) as usize + 2 * (p.y * 3.0 + 1e-6) as usize;
+                assert!(!seen[cell]);
+                seen[cell] = true;
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn stratified_rejects_empty_grid() {
+        Stratified::new(4, 0);
+    }
+
+    #[test]
+    fn stratified_and_r2() {
+        let s = Stratified::jittered(4, 3, 1);
+        let mut seen = [false; 12];
+        for i in 0..12 {
+            let p: Vector2<f32> = s.sample2(i);
+            let cell = (p.x * 4.0) as usize + 4 * (p.y * 3.0) as usize;
+            assert!(!seen[cell]);
+            seen[cell] = true;
+        }
+        assert_eq!(
+            Stratified::new(2, 2).sample2::<f32>(3),
+            Vector2::new(0.75, 0.75)
+        );
+        let big = Stratified::new(1 << 16, 1 << 16);
+        assert_eq!(big.sample2::<f64>(u32::MAX).y, 1.0 - 0.5 / 65536.0);
+
+        let r = R2::new();
+        let p: Vector2<f64> = r.sample2(1);
+        assert!((p.x - (0.5 + 1.0 / 1.324_717_957_244_746) % 1.0).abs() < 1e-9);
+        assert!((p.y - (0.5 + 1.0 / 1.754_877_666_246_693) % 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sequence_warped_to_hemisphere() {
+        // Estimates the integral of cos(theta) over the hemisphere, which is pi.
+        let n = 1024;
+        let s = Sobol::scrambled(Scramble::Owen(1));
+        let estimate: f64 = (0..n)
+            .map(|i| {
+                let d = crate::sampling::uniform_hemisphere(&s.sample2::<f64>(i));
+                d.z / crate::sampling::uniform_hemisphere_pdf::<f64>()
+            })
+            .sum::<f64>()
+            / n as f64;
+        assert!((estimate - core::f64::consts::PI).abs() < 1e-3);
+    }
+}
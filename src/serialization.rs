@@ -0,0 +1,286 @@
+//! Serde support, enabled by the `serde` feature.
+//!
+//! Vectors serialize as arrays (`[x, y, z]`) and matrices as arrays of columns. Wrap a value
+//! in [`Named`], or annotate a field with `#[serde(with = "linmath::serialization::named")]`,
+//! to use maps with the field names as keys instead.
+
+use crate::matrix::*;
+use crate::vector::*;
+use crate::wide::*;
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeStruct, SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+
+/// Serializes the wrapped vector or matrix as a map keyed by field name.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Named<V>(pub V);
+
+/// For use with `#[serde(with = "linmath::serialization::named")]`.
+pub mod named {
+    use super::Named;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<V, S: Serializer>(v: &V, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        for<'a> Named<&'a V>: Serialize,
+    {
+        Named(v).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, V, D: Deserializer<'de>>(deserializer: D) -> Result<V, D::Error>
+    where
+        Named<V>: Deserialize<'de>,
+    {
+        Named::deserialize(deserializer).map(|n| n.0)
+    }
+}
+
+// Deserializes a map key into the index of a field, by name or by position.
+struct FieldIndex(&'static [&'static str]);
+
+impl<'de> DeserializeSeed<'de> for FieldIndex {
+    type Value = usize;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<usize, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldIndex {
+    type Value = usize;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "one of {:?}", self.0)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<usize, E> {
+        match self.0.get(v as usize) {
+            Some(_) => Ok(v as usize),
+            None => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<usize, E> {
+        match self.0.iter().position(|f| *f == v) {
+            Some(i) => Ok(i),
+            None => Err(E::unknown_field(v, self.0)),
+        }
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<usize, E> {
+        match self.0.iter().position(|f| f.as_bytes() == v) {
+            Some(i) => Ok(i),
+            None => Err(E::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+// `leaf` types have scalar or array fields, `nested` types have vector fields which are named
+// as well. `$Elem` is the scalar type the (de)serialization is bounded on.
+macro_rules! named_field_ser {
+    (leaf, $v: expr) => {
+        $v
+    };
+    (nested, $v: expr) => {
+        &Named($v)
+    };
+}
+
+macro_rules! named_element_de {
+    (leaf, $seq: ident) => {
+        $seq.next_element()?
+    };
+    (nested, $seq: ident) => {
+        $seq.next_element::<Named<_>>()?.map(|n| n.0)
+    };
+}
+
+macro_rules! named_value_de {
+    (leaf, $map: ident) => {
+        $map.next_value()?
+    };
+    (nested, $map: ident) => {
+        $map.next_value::<Named<_>>()?.0
+    };
+}
+
+macro_rules! impl_serde {
+    ($kind: ident, $Type: ident $(<$T: ident>)?, $Elem: ty, $len: expr, $($field: ident),+) => {
+        impl<$($T)?> Serialize for $Type$(<$T>)? where $Elem: Serialize {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut t = serializer.serialize_tuple($len)?;
+                $(t.serialize_element(&self.$field)?;)+
+                t.end()
+            }
+        }
+
+        impl<'de, $($T)?> Deserialize<'de> for $Type$(<$T>)? where $Elem: Deserialize<'de> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct ArrayVisitor<$($T)?>(PhantomData<$Type$(<$T>)?>);
+
+                impl<'de, $($T)?> Visitor<'de> for ArrayVisitor<$($T)?> where $Elem: Deserialize<'de> {
+                    type Value = $Type$(<$T>)?;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        write!(f, "an array of length {}", $len)
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                        let mut i = 0;
+                        $(
+                            let $field = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                            i += 1;
+                        )+
+                        let _ = i;
+                        Ok($Type { $($field),+ })
+                    }
+                }
+
+                deserializer.deserialize_tuple($len, ArrayVisitor(PhantomData))
+            }
+        }
+
+        impl<'a, $($T)?> Serialize for Named<&'a $Type$(<$T>)?> where $Elem: Serialize {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut s = serializer.serialize_struct(stringify!($Type), $len)?;
+                $(s.serialize_field(stringify!($field), named_field_ser!($kind, &self.0.$field))?;)+
+                s.end()
+            }
+        }
+
+        impl<$($T)?> Serialize for Named<$Type$(<$T>)?> where $Elem: Serialize {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                Named(&self.0).serialize(serializer)
+            }
+        }
+
+        impl<'de, $($T)?> Deserialize<'de> for Named<$Type$(<$T>)?> where $Elem: Deserialize<'de> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                const FIELDS: &[&str] = &[$(stringify!($field)),+];
+
+                struct MapVisitor<$($T)?>(PhantomData<$Type$(<$T>)?>);
+
+                impl<'de, $($T)?> Visitor<'de> for MapVisitor<$($T)?> where $Elem: Deserialize<'de> {
+                    type Value = Named<$Type$(<$T>)?>;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        write!(f, "a map with the keys {:?}", FIELDS)
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                        let mut i = 0;
+                        $(
+                            let $field = named_element_de!($kind, seq)
+                                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                            i += 1;
+                        )+
+                        let _ = i;
+                        Ok(Named($Type { $($field),+ }))
+                    }
+
+                    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                        $(let mut $field = None;)+
+                        while let Some(key) = map.next_key_seed(FieldIndex(FIELDS))? {
+                            let mut i = 0;
+                            $(
+                                if key == i {
+                                    if $field.is_some() {
+                                        return Err(de::Error::duplicate_field(FIELDS[i]));
+                                    }
+                                    $field = Some(named_value_de!($kind, map));
+                                }
+                                i += 1;
+                            )+
+                            let _ = i;
+                        }
+                        Ok(Named($Type {
+                            $($field: $field.ok_or_else(|| de::Error::missing_field(stringify!($field)))?),+
+                        }))
+                    }
+                }
+
+                deserializer.deserialize_struct(stringify!($Type), FIELDS, MapVisitor(PhantomData))
+            }
+        }
+    };
+}
+
+impl_serde!(leaf, BVec2, bool, 2, x, y);
+impl_serde!(leaf, BVec3, bool, 3, x, y, z);
+impl_serde!(leaf, BVec4, bool, 4, x, y, z, w);
+impl_serde!(leaf, Vector2<T>, T, 2, x, y);
+impl_serde!(leaf, Vector3<T>, T, 3, x, y, z);
+impl_serde!(leaf, Vector4<T>, T, 4, x, y, z, w);
+impl_serde!(nested, Matrix2<T>, T, 2, x, y);
+impl_serde!(nested, Matrix3<T>, T, 3, x, y, z);
+impl_serde!(nested, Matrix4<T>, T, 4, x, y, z, w);
+impl_serde!(leaf, Vector3x4<T>, T, 3, x, y, z);
+impl_serde!(leaf, Vector3x8<T>, T, 3, x, y, z);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vector_serde_array() {
+        let v = Vector3::new(1.0, 2.0, 3.0);
+        let s = serde_json::to_string(&v).unwrap();
+        assert_eq!(s, "[1.0,2.0,3.0]");
+        assert_eq!(serde_json::from_str::<Vector3<f64>>(&s).unwrap(), v);
+        assert!(serde_json::from_str::<Vector3<f64>>("[1.0,2.0]").is_err());
+        let b = BVec2::new(true, false);
+        assert_eq!(serde_json::to_string(&b).unwrap(), "[true,false]");
+    }
+
+    #[test]
+    fn matrix_serde_nested_arrays() {
+        let m = Matrix2::new(Vector2::new(1, 2), Vector2::new(3, 4));
+        let s = serde_json::to_string(&m).unwrap();
+        assert_eq!(s, "[[1,2],[3,4]]");
+        assert_eq!(serde_json::from_str::<Matrix2<i32>>(&s).unwrap(), m);
+        let m = Matrix4::<f32>::identity();
+        let s = serde_json::to_string(&m).unwrap();
+        assert_eq!(serde_json::from_str::<Matrix4<f32>>(&s).unwrap(), m);
+    }
+
+    #[test]
+    fn named_serde_map() {
+        let v = Named(Vector2::new(1, 2));
+        let s = serde_json::to_string(&v).unwrap();
+        assert_eq!(s, r#"{"x":1,"y":2}"#);
+        assert_eq!(
+            serde_json::from_str::<Named<Vector2<i32>>>(r#"{"y":2,"x":1}"#).unwrap(),
+            v
+        );
+        assert!(serde_json::from_str::<Named<Vector2<i32>>>(r#"{"x":1}"#).is_err());
+        assert!(serde_json::from_str::<Named<Vector2<i32>>>(r#"{"x":1,"y":2,"z":3}"#).is_err());
+
+        let m = Named(Matrix2::new(Vector2::new(1, 2), Vector2::new(3, 4)));
+        let s = serde_json::to_string(&m).unwrap();
+        assert_eq!(s, r#"{"x":{"x":1,"y":2},"y":{"x":3,"y":4}}"#);
+        assert_eq!(serde_json::from_str::<Named<Matrix2<i32>>>(&s).unwrap(), m);
+    }
+
+    #[test]
+    fn named_serde_with() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Transform {
+            #[serde(with = "named")]
+            position: Vector3<f32>,
+            rotation: Vector4<f32>,
+        }
+
+        let t = Transform {
+            position: Vector3::new(1.0, 2.0, 3.0),
+            rotation: Vector4::new(0.0, 0.0, 0.0, 1.0),
+        };
+        let s = serde_json::to_string(&t).unwrap();
+        assert_eq!(
+            s,
+            r#"{"position":{"x":1.0,"y":2.0,"z":3.0},"rotation":[0.0,0.0,0.0,1.0]}"#
+        );
+        assert_eq!(serde_json::from_str::<Transform>(&s).unwrap(), t);
+    }
+}
@@ -0,0 +1,313 @@
+// Compile-time selected `f32` implementations of the `Matrix4` hooks in `Scalar` and
+// `SignedScalar`. x86_64 uses SSE2, with AVX for matrix products when the target enables
+// it; every other target, or builds without the `simd` feature, use the scalar code.
+// There is no NEON path: aarch64 is left to the scalar code and the autovectorizer.
+//
+// Products keep the association order of `Vector4::dot` so results are bit-identical to
+// the scalar implementation.
+
+use crate::matrix::Matrix4;
+use crate::vector::Vector4;
+
+#[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "sse2"))]
+mod sse2 {
+    use crate::matrix::Matrix4;
+    use crate::vector::Vector4;
+    use core::arch::x86_64::*;
+
+    macro_rules! shuffle {
+        ($a: expr, $b: expr, $x: expr, $y: expr, $z: expr, $w: expr) => {
+            _mm_shuffle_ps::<{ $x | ($y << 2) | ($z << 4) | ($w << 6) }>($a, $b)
+        };
+    }
+
+    #[inline(always)]
+    pub unsafe fn load(v: &Vector4<f32>) -> __m128 {
+        _mm_loadu_ps(v as *const Vector4<f32> as *const f32)
+    }
+
+    #[inline(always)]
+    pub unsafe fn store(r: __m128) -> Vector4<f32> {
+        let mut v = Vector4::new(0.0, 0.0, 0.0, 0.0);
+        _mm_storeu_ps(&mut v as *mut Vector4<f32> as *mut f32, r);
+        v
+    }
+
+    #[inline(always)]
+    pub unsafe fn columns(m: &Matrix4<f32>) -> [__m128; 4] {
+        [load(&m.x), load(&m.y), load(&m.z), load(&m.w)]
+    }
+
+    #[inline(always)]
+    unsafe fn combine(c: &[__m128; 4], v: __m128) -> __m128 {
+        let x = _mm_mul_ps(c[0], shuffle!(v, v, 0, 0, 0, 0));
+        let y = _mm_mul_ps(c[1], shuffle!(v, v, 1, 1, 1, 1));
+        let z = _mm_mul_ps(c[2], shuffle!(v, v, 2, 2, 2, 2));
+        let w = _mm_mul_ps(c[3], shuffle!(v, v, 3, 3, 3, 3));
+        _mm_add_ps(x, _mm_add_ps(y, _mm_add_ps(z, w)))
+    }
+
+    #[inline]
+    pub unsafe fn mul_matrix4_and_vector4(a: &Matrix4<f32>, b: &Vector4<f32>) -> Vector4<f32> {
+        store(combine(&columns(a), load(b)))
+    }
+
+    #[cfg(not(target_feature = "avx"))]
+    #[inline]
+    pub unsafe fn mul_matrix4_and_matrix4(a: &Matrix4<f32>, b: &Matrix4<f32>) -> Matrix4<f32> {
+        let c = columns(a);
+        Matrix4::new(
+            store(combine(&c, load(&b.x))),
+            store(combine(&c, load(&b.y))),
+            store(combine(&c, load(&b.z))),
+            store(combine(&c, load(&b.w))),
+        )
+    }
+
+    #[inline]
+    pub unsafe fn transpose_matrix4(m: &Matrix4<f32>) -> Matrix4<f32> {
+        let [x, y, z, w] = columns(m);
+        let t0 = _mm_unpacklo_ps(x, y);
+        let t1 = _mm_unpacklo_ps(z, w);
+        let t2 = _mm_unpackhi_ps(x, y);
+        let t3 = _mm_unpackhi_ps(z, w);
+        Matrix4::new(
+            store(_mm_movelh_ps(t0, t1)),
+            store(_mm_movehl_ps(t1, t0)),
+            store(_mm_movelh_ps(t2, t3)),
+            store(_mm_movehl_ps(t3, t2)),
+        )
+    }
+
+    // 2x2 blocks are stored as (m00, m01, m10, m11).
+    #[inline(always)]
+    unsafe fn mat2_mul(a: __m128, b: __m128) -> __m128 {
+        _mm_add_ps(
+            _mm_mul_ps(a, shuffle!(b, b, 0, 3, 0, 3)),
+            _mm_mul_ps(shuffle!(a, a, 1, 0, 3, 2), shuffle!(b, b, 2, 1, 2, 1)),
+        )
+    }
+
+    // adj(a) * b
+    #[inline(always)]
+    unsafe fn mat2_adj_mul(a: __m128, b: __m128) -> __m128 {
+        _mm_sub_ps(
+            _mm_mul_ps(shuffle!(a, a, 3, 3, 0, 0), b),
+            _mm_mul_ps(shuffle!(a, a, 1, 1, 2, 2), shuffle!(b, b, 2, 3, 0, 1)),
+        )
+    }
+
+    // a * adj(b)
+    #[inline(always)]
+    unsafe fn mat2_mul_adj(a: __m128, b: __m128) -> __m128 {
+        _mm_sub_ps(
+            _mm_mul_ps(a, shuffle!(b, b, 3, 0, 3, 0)),
+            _mm_mul_ps(shuffle!(a, a, 1, 0, 3, 2), shuffle!(b, b, 2, 1, 2, 1)),
+        )
+    }
+
+    // Blockwise inversion; the same code inverts row and column major layouts since
+    // inv(transpose(M)) = transpose(inv(M)).
+    pub unsafe fn inverse_matrix4(m: &Matrix4<f32>) -> Option<Matrix4<f32>> {
+        let [c0, c1, c2, c3] = columns(m);
+        let a = _mm_movelh_ps(c0, c1);
+        let b = _mm_movehl_ps(c1, c0);
+        let c = _mm_movelh_ps(c2, c3);
+        let d = _mm_movehl_ps(c3, c2);
+
+        let det_sub = _mm_sub_ps(
+            _mm_mul_ps(shuffle!(c0, c2, 0, 2, 0, 2), shuffle!(c1, c3, 1, 3, 1, 3)),
+            _mm_mul_ps(shuffle!(c0, c2, 1, 3, 1, 3), shuffle!(c1, c3, 0, 2, 0, 2)),
+        );
+        let det_a = shuffle!(det_sub, det_sub, 0, 0, 0, 0);
+        let det_b = shuffle!(det_sub, det_sub, 1, 1, 1, 1);
+        let det_c = shuffle!(det_sub, det_sub, 2, 2, 2, 2);
+        let det_d = shuffle!(det_sub, det_sub, 3, 3, 3, 3);
+
+        let d_c = mat2_adj_mul(d, c);
+        let a_b = mat2_adj_mul(a, b);
+        let x = _mm_sub_ps(_mm_mul_ps(det_d, a), mat2_mul(b, d_c));
+        let w = _mm_sub_ps(_mm_mul_ps(det_a, d), mat2_mul(c, a_b));
+        let y = _mm_sub_ps(_mm_mul_ps(det_b, c), mat2_mul_adj(d, a_b));
+        let z = _mm_sub_ps(_mm_mul_ps(det_c, b), mat2_mul_adj(a, d_c));
+
+        // The determinant and singularity test are those of `Matrix4::inverse_fallback`,
+        // so both paths agree on which matrices are invertible.
+        let det = Matrix4::determinant(m);
+        if det == 0.0 {
+            return None;
+        }
+
+        let r = _mm_mul_ps(_mm_setr_ps(1.0, -1.0, -1.0, 1.0), _mm_set1_ps(1.0 / det));
+        let x = _mm_mul_ps(x, r);
+        let y = _mm_mul_ps(y, r);
+        let z = _mm_mul_ps(z, r);
+        let w = _mm_mul_ps(w, r);
+        Some(Matrix4::new(
+            store(shuffle!(x, y, 3, 1, 3, 1)),
+            store(shuffle!(x, y, 2, 0, 2, 0)),
+            store(shuffle!(z, w, 3, 1, 3, 1)),
+            store(shuffle!(z, w, 2, 0, 2, 0)),
+        ))
+    }
+
+    // Computes two result columns per 256-bit register.
+    #[cfg(target_feature = "avx")]
+    #[inline]
+    pub unsafe fn mul_matrix4_and_matrix4_avx(a: &Matrix4<f32>, b: &Matrix4<f32>) -> Matrix4<f32> {
+        let [x, y, z, w] = columns(a);
+        let (x, y, z, w) = (
+            _mm256_set_m128(x, x),
+            _mm256_set_m128(y, y),
+            _mm256_set_m128(z, z),
+            _mm256_set_m128(w, w),
+        );
+        let combine = |v: __m256| {
+            let vx = _mm256_mul_ps(x, _mm256_shuffle_ps::<0x00>(v, v));
+            let vy = _mm256_mul_ps(y, _mm256_shuffle_ps::<0x55>(v, v));
+            let vz = _mm256_mul_ps(z, _mm256_shuffle_ps::<0xaa>(v, v));
+            let vw = _mm256_mul_ps(w, _mm256_shuffle_ps::<0xff>(v, v));
+            _mm256_add_ps(vx, _mm256_add_ps(vy, _mm256_add_ps(vz, vw)))
+        };
+        let p = b as *const Matrix4<f32> as *const f32;
+        let xy = combine(_mm256_loadu_ps(p));
+        let zw = combine(_mm256_loadu_ps(p.add(8)));
+        let mut r = Matrix4::new(
+            Vector4::new(0.0, 0.0, 0.0, 0.0),
+            Vector4::new(0.0, 0.0, 0.0, 0.0),
+            Vector4::new(0.0, 0.0, 0.0, 0.0),
+            Vector4::new(0.0, 0.0, 0.0, 0.0),
+        );
+        let q = &mut r as *mut Matrix4<f32> as *mut f32;
+        _mm256_storeu_ps(q, xy);
+        _mm256_storeu_ps(q.add(8), zw);
+        r
+    }
+}
+
+#[inline]
+pub(crate) fn mul_matrix4_and_matrix4(a: &Matrix4<f32>, b: &Matrix4<f32>) -> Matrix4<f32> {
+    #[cfg(all(
+        feature = "simd",
+        target_arch = "x86_64",
+        target_feature = "sse2",
+        target_feature = "avx"
+    ))]
+    return unsafe { sse2::mul_matrix4_and_matrix4_avx(a, b) };
+    #[cfg(all(
+        feature = "simd",
+        target_arch = "x86_64",
+        target_feature = "sse2",
+        not(target_feature = "avx")
+    ))]
+    return unsafe { sse2::mul_matrix4_and_matrix4(a, b) };
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64", target_feature = "sse2")))]
+    return Matrix4::mul_matrix_and_matrix_fallback(a, b);
+}
+
+#[inline]
+pub(crate) fn mul_matrix4_and_vector4(a: &Matrix4<f32>, b: &Vector4<f32>) -> Vector4<f32> {
+    #[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "sse2"))]
+    return unsafe { sse2::mul_matrix4_and_vector4(a, b) };
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64", target_feature = "sse2")))]
+    return Matrix4::mul_matrix_and_vector_fallback(a, b);
+}
+
+#[inline]
+pub(crate) fn transpose_matrix4(m: &Matrix4<f32>) -> Matrix4<f32> {
+    #[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "sse2"))]
+    return unsafe { sse2::transpose_matrix4(m) };
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64", target_feature = "sse2")))]
+    return Matrix4::transpose_fallback(m);
+}
+
+#[inline]
+pub(crate) fn inverse_matrix4(m: &Matrix4<f32>) -> Option<Matrix4<f32>> {
+    #[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "sse2"))]
+    return unsafe { sse2::inverse_matrix4(m) };
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64", target_feature = "sse2")))]
+    return Matrix4::inverse_fallback(m);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(seed: f32) -> Matrix4<f32> {
+        let v = |i: f32| ((seed + i) * 12.9898).sin() * 4.0;
+        Matrix4::new(
+            Vector4::new(v(0.0), v(1.0), v(2.0), v(3.0)),
+            Vector4::new(v(4.0), v(5.0), v(6.0), v(7.0)),
+            Vector4::new(v(8.0), v(9.0), v(10.0), v(11.0)),
+            Vector4::new(v(12.0), v(13.0), v(14.0), v(15.0)),
+        )
+    }
+
+    #[test]
+    fn simd_mul_matrix4_matches_fallback() {
+        for i in 0..16 {
+            let (a, b) = (m(i as f32), m(i as f32 + 0.5));
+            assert_eq!(a * b, Matrix4::mul_matrix_and_matrix_fallback(&a, &b));
+        }
+    }
+
+    #[test]
+    fn simd_mul_vector4_matches_fallback() {
+        for i in 0..16 {
+            let a = m(i as f32);
+            let v = m(i as f32 + 0.5).y;
+            assert_eq!(a * v, Matrix4::mul_matrix_and_vector_fallback(&a, &v));
+        }
+    }
+
+    #[test]
+    fn simd_transpose_matches_fallback() {
+        let a = m(1.0);
+        assert_eq!(Matrix4::transpose(&a), Matrix4::transpose_fallback(&a));
+    }
+
+    #[test]
+    fn simd_inverse_matches_fallback() {
+        for i in 0..16 {
+            // Diagonally dominant, hence well conditioned.
+            let a = m(i as f32) + Matrix4::identity() * 16.0;
+            let inv = Matrix4::inverse(&a).unwrap();
+            let expected = Matrix4::inverse_fallback(&a).unwrap();
+            assert_relative_eq!(inv, expected, epsilon = 1e-4, max_relative = 1e-3);
+            assert_relative_eq!(a * inv, Matrix4::identity(), epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn simd_inverse_singular() {
+        let a = Matrix4::new(
+            Vector4::new(1.0f32, 2.0, 3.0, 4.0),
+            Vector4::new(2.0, 4.0, 6.0, 8.0),
+            Vector4::new(0.0, 1.0, 0.0, 1.0),
+            Vector4::new(1.0, 0.0, 1.0, 0.0),
+        );
+        assert_eq!(Matrix4::inverse(&a), None);
+    }
+
+    #[test]
+    fn simd_inverse_singular_matches_fallback() {
+        for i in 0..64 {
+            let mut a = m(i as f32);
+            // Make the last column a combination of the others, whose determinant rounds
+            // to zero or not depending on the values.
+            a.w = a.x * 0.5 + a.y * 0.25 - a.z;
+            let (inv, expected) = (Matrix4::inverse(&a), Matrix4::inverse_fallback(&a));
+            assert_eq!(inv.is_none(), expected.is_none());
+            if let (Some(inv), Some(expected)) = (inv, expected) {
+                // Compare the adjugates, the inverses themselves are huge.
+                let det = Matrix4::determinant(&a);
+                assert_relative_eq!(
+                    inv * det,
+                    expected * det,
+                    epsilon = 1e-2,
+                    max_relative = 1e-3
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use crate::aabb::Aabb3;
+use crate::matrix::Matrix4;
+use crate::scalar::Real;
+use crate::vector::*;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sphere<T> {
+    pub center: Vector3<T>,
+    pub radius: T,
+}
+
+impl<T> Sphere<T> {
+    #[inline]
+    pub fn new(center: Vector3<T>, radius: T) -> Sphere<T> {
+        Sphere { center, radius }
+    }
+}
+
+#[inline]
+fn distance2<T: Real>(a: &Vector3<T>, b: &Vector3<T>) -> T {
+    let d = *a - *b;
+    Vector3::dot(&d, &d)
+}
+
+impl<T: Real> Sphere<T> {
+    #[inline]
+    pub fn contains(s: &Sphere<T>, p: &Vector3<T>) -> bool {
+        distance2(&s.center, p) <= s.radius * s.radius
+    }
+
+    #[inline]
+    pub fn intersects(a: &Sphere<T>, b: &Sphere<T>) -> bool {
+        let r = a.radius + b.radius;
+        distance2(&a.center, &b.center) <= r * r
+    }
+
+    #[inline]
+    pub fn intersects_aabb(s: &Sphere<T>, b: &Aabb3<T>) -> bool {
+        Sphere::contains(s, &Aabb3::closest_point(b, &s.center))
+    }
+
+    /// Sphere enclosing `s` transformed by the affine `m`. Exact for similarity
+    /// transforms, otherwise the radius is scaled by the largest axis scale.
+    pub fn transform(m: &Matrix4<T>, s: &Sphere<T>) -> Sphere<T> {
+        let scale2 = [&m.x, &m.y, &m.z]
+            .iter()
+            .map(|c| c.x * c.x + c.y * c.y + c.z * c.z)
+            .fold(T::zero(), |a, b| if b > a { b } else { a });
+        Sphere::new(
+            Matrix4::transform_point(m, &s.center),
+            s.radius * scale2.sqrt(),
+        )
+    }
+
+    /// Ritter's bounding sphere: fast, and typically 5 to 20 percent larger than the
+    /// minimal one. `None` if there are no points.
+    pub fn from_points_ritter(points: &[Vector3<T>]) -> Option<Sphere<T>> {
+        let first = points.first()?;
+        let farthest = |from: &Vector3<T>| {
+            points.iter().fold(*first, |best, p| {
+                if distance2(from, p) > distance2(from, &best) {
+                    *p
+                } else {
+                    best
+                }
+            })
+        };
+        let a = farthest(first);
+        let b = farthest(&a);
+        let mut s = sphere2(&a, &b);
+        for p in points {
+            let d2 = distance2(&s.center, p);
+            if d2 > s.radius * s.radius {
+                // Grow just enough to reach `p`, keeping the far side in place.
+                let d = d2.sqrt();
+                let radius = (s.radius + d) / (T::one() + T::one());
+                s.center = s.center + (*p - s.center) * ((radius - s.radius) / d);
+                s.radius = radius;
+            }
+        }
+        Some(s)
+    }
+
+    /// Welzl's minimal enclosing sphere. The points are visited in a fixed pseudo-random
+    /// order, which gives expected linear time unless the input is adversarial. `None` if
+    /// there are no points.
+    pub fn from_points_welzl(points: &[Vector3<T>]) -> Option<Sphere<T>> {
+        // Iterative form of the recursion: each level fixes one more point on the boundary.
+        let order = Shuffle::new(points.len());
+        let p = |i: usize| &points[order.get(i)];
+        if points.is_empty() {
+            return None;
+        }
+        let mut s = Sphere::new(*p(0), T::zero());
+        for i in 1..points.len() {
+            if contains_loose(&s, p(i)) {
+                continue;
+            }
+            s = Sphere::new(*p(i), T::zero());
+            for j in 0..i {
+                if contains_loose(&s, p(j)) {
+                    continue;
+                }
+                s = sphere2(p(i), p(j));
+                for k in 0..j {
+                    if contains_loose(&s, p(k)) {
+                        continue;
+                    }
+                    s = sphere3(p(i), p(j), p(k));
+                    for l in 0..k {
+                        if !contains_loose(&s, p(l)) {
+                            s = sphere4(p(i), p(j), p(k), p(l));
+                        }
+                    }
+                }
+            }
+        }
+        Some(s)
+    }
+}
+
+// Pseudo-random permutation of `0..n` without an index buffer: a bijective mix of
+// multiply-xorshift rounds on the next power of two, cycle-walked until it lands below `n`,
+// which takes fewer than two rounds on average.
+struct Shuffle {
+    n: usize,
+    mask: u64,
+    shift: u32,
+}
+
+impl Shuffle {
+    fn new(n: usize) -> Shuffle {
+        let bits = usize::BITS - n.saturating_sub(1).leading_zeros();
+        Shuffle {
+            n,
+            mask: u64::MAX.checked_shr(64 - bits).unwrap_or(0),
+            shift: (bits / 2).max(1),
+        }
+    }
+
+    fn get(&self, i: usize) -> usize {
+        let mut x = i as u64;
+        loop {
+            // Odd multipliers and xorshifts are bijections on the masked bits.
+            x = x.wrapping_mul(0x9e37_79b9_7f4a_7c15) & self.mask;
+            x ^= x >> self.shift;
+            x = x.wrapping_mul(0xbf58_476d_1ce4_e5b9) & self.mask;
+            x ^= x >> self.shift;
+            if (x as usize) < self.n {
+                return x as usize;
+            }
+        }
+    }
+}
+
+// Containment with a small relative tolerance, so points on the boundary of a sphere built
+// through them are not reported outside due to rounding.
+#[inline]
+fn contains_loose<T: Real>(s: &Sphere<T>, p: &Vector3<T>) -> bool {
+    let r2 = s.radius * s.radius;
+    distance2(&s.center, p) <= r2 + r2 * T::from_f64(1e-9)
+}
+
+fn sphere2<T: Real>(a: &Vector3<T>, b: &Vector3<T>) -> Sphere<T> {
+    let half = T::from_f64(0.5);
+    let center = (*a + *b) * half;
+    Sphere::new(center, distance2(a, b).sqrt() * half)
+}
+
+// The smallest sphere through three points, or through the farthest two if collinear.
+fn sphere3<T: Real>(a: &Vector3<T>, b: &Vector3<T>, c: &Vector3<T>) -> Sphere<T> {
+    let (ab, ac) = (*b - *a, *c - *a);
+    let n = Vector3::cross(&ab, &ac);
+    let n2 = Vector3::dot(&n, &n);
+    if n2 == T::zero() {
+        let pairs = [sphere2(a, b), sphere2(a, c), sphere2(b, c)];
+        return pairs
+            .iter()
+            .fold(pairs[0], |m, s| if s.radius > m.radius { *s } else { m });
+    }
+    let o = (Vector3::cross(&n, &ab) * Vector3::dot(&ac, &ac)
+        + Vector3::cross(&ac, &n) * Vector3::dot(&ab, &ab))
+        * (T::from_f64(0.5) / n2);
+    Sphere::new(*a + o, Vector3::length(&o))
+}
+
+// The sphere through four points, or the smallest of the spheres through three of them
+// enclosing the fourth if they are coplanar.
+fn sphere4<T: Real>(a: &Vector3<T>, b: &Vector3<T>, c: &Vector3<T>, d: &Vector3<T>) -> Sphere<T> {
+    let (ab, ac, ad) = (*b - *a, *c - *a, *d - *a);
+    let det = Vector3::dot(&ab, &Vector3::cross(&ac, &ad));
+    if det == T::zero() {
+        let candidates = [
+            (sphere3(a, b, c), d),
+            (sphere3(a, b, d), c),
+            (sphere3(a, c, d), b),
+            (sphere3(b, c, d), a),
+        ];
+        return candidates
+            .iter()
+            .filter(|(s, p)| contains_loose(s, p))
+            .map(|(s, _)| *s)
+            .fold(None, |m: Option<Sphere<T>>, s| match m {
+                Some(m) if m.radius <= s.radius => Some(m),
+                _ => Some(s),
+            })
+            .unwrap_or(candidates[0].0);
+    }
+    let o = (Vector3::cross(&ac, &ad) * Vector3::dot(&ab, &ab)
+        + Vector3::cross(&ad, &ab) * Vector3::dot(&ac, &ac)
+        + Vector3::cross(&ab, &ac) * Vector3::dot(&ad, &ad))
+        * (T::from_f64(0.5) / det);
+    Sphere::new(*a + o, Vector3::length(&o))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn sphere_tests() {
+        let s = Sphere::new(Vector3::new(0.0, 0.0, 0.0), 1.0);
+        assert!(Sphere::contains(&s, &Vector3::new(0.0, 1.0, 0.0)));
+        assert!(!Sphere::contains(&s, &Vector3::new(0.8, 0.8, 0.0)));
+        assert!(Sphere::intersects(
+            &s,
+            &Sphere::new(Vector3::new(2.5, 0.0, 0.0), 1.5)
+        ));
+        assert!(!Sphere::intersects(
+            &s,
+            &Sphere::new(Vector3::new(2.5, 0.0, 0.0), 1.4)
+        ));
+        let b = Aabb3::new(Vector3::new(0.8, 0.8, -1.0), Vector3::new(2.0, 2.0, 1.0));
+        assert!(!Sphere::intersects_aabb(&s, &b));
+        let b = Aabb3::new(Vector3::new(0.7, 0.7, -1.0), Vector3::new(2.0, 2.0, 1.0));
+        assert!(Sphere::intersects_aabb(&s, &b));
+
+        let m = Matrix4::translate(&Vector3::new(1.0, 2.0, 3.0))
+            * Matrix4::scale(&Vector3::new(2.0, 3.0, 1.0));
+        let t = Sphere::transform(&m, &s);
+        assert_eq!(t, Sphere::new(Vector3::new(1.0, 2.0, 3.0), 3.0));
+    }
+
+    #[test]
+    fn sphere_from_points() {
+        // The minimal sphere of a regular tetrahedron is its circumsphere.
+        let tetra = [
+            Vector3::new(1.0, 1.0, 1.0),
+            Vector3::new(1.0, -1.0, -1.0),
+            Vector3::new(-1.0, 1.0, -1.0),
+            Vector3::new(-1.0, -1.0, 1.0),
+        ];
+        let s = Sphere::from_points_welzl(&tetra).unwrap();
+        assert_relative_eq!(s.center, Vector3::new(0.0, 0.0, 0.0), epsilon = 1e-12);
+        assert_relative_eq!(s.radius, 3.0f64.sqrt(), epsilon = 1e-12);
+
+        // Two far points and an obtuse triangle in between: the diameter sphere.
+        let s = Sphere::from_points_welzl(&[
+            Vector3::new(-2.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.5, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+        ])
+        .unwrap();
+        assert_relative_eq!(s.center, Vector3::new(0.0, 0.0, 0.0));
+        assert_relative_eq!(s.radius, 2.0);
+        assert_eq!(Sphere::<f64>::from_points_welzl(&[]), None);
+
+        let mut rng = SmallRng::seed_from_u64(3);
+        let points: Vec<Vector3<f64>> = (0..200)
+            .map(|_| Vector3::new(rng.gen(), rng.gen::<f64>() * 2.0, rng.gen::<f64>() * 0.5))
+            .collect();
+        let welzl = Sphere::from_points_welzl(&points).unwrap();
+        let ritter = Sphere::from_points_ritter(&points).unwrap();
+        for p in &points {
+            assert!(contains_loose(&welzl, p));
+            assert!(contains_loose(&ritter, p));
+        }
+        assert!(welzl.radius <= ritter.radius);
+        // At least two points lie on the minimal sphere.
+        let on = points
+            .iter()
+            .filter(|p| (distance2(&welzl.center, p).sqrt() - welzl.radius).abs() < 1e-9)
+            .count();
+        assert!(on >= 2);
+
+        // Sorted input, where every point in order lies outside the previous sphere.
+        let line: Vec<Vector3<f64>> = (0..20_000)
+            .map(|i| Vector3::new(i as f64, (i as f64 * 0.1).sin(), 0.0))
+            .collect();
+        let s = Sphere::from_points_welzl(&line).unwrap();
+        assert!(line.iter().all(|p| contains_loose(&s, p)));
+        assert!(s.radius < 10_000.0);
+    }
+
+    #[test]
+    fn shuffle_is_permutation() {
+        for n in [0, 1, 2, 3, 7, 8, 9, 100, 1000] {
+            let order = Shuffle::new(n);
+            let mut seen = vec![false; n];
+            for i in 0..n {
+                let j = order.get(i);
+                assert!(!seen[j]);
+                seen[j] = true;
+            }
+        }
+        // The widest lengths use every bit of the mask.
+        let order = Shuffle::new(usize::MAX);
+        assert_eq!(order.mask, usize::MAX as u64);
+        assert!(order.get(usize::MAX - 1) < usize::MAX);
+    }
+}
@@ -0,0 +1,426 @@
+use crate::matrix::Matrix4;
+use crate::scalar::{Real, SignedScalar};
+use crate::vector::*;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Triangle2<T> {
+    pub a: Vector2<T>,
+    pub b: Vector2<T>,
+    pub c: Vector2<T>,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Triangle3<T> {
+    pub a: Vector3<T>,
+    pub b: Vector3<T>,
+    pub c: Vector3<T>,
+}
+
+impl<T> Triangle2<T> {
+    #[inline]
+    pub fn new(a: Vector2<T>, b: Vector2<T>, c: Vector2<T>) -> Triangle2<T> {
+        Triangle2 { a, b, c }
+    }
+}
+
+impl<T> Triangle3<T> {
+    #[inline]
+    pub fn new(a: Vector3<T>, b: Vector3<T>, c: Vector3<T>) -> Triangle3<T> {
+        Triangle3 { a, b, c }
+    }
+}
+
+#[inline]
+fn cross2<T: SignedScalar>(u: &Vector2<T>, v: &Vector2<T>) -> T {
+    u.x * v.y - u.y * v.x
+}
+
+impl<T: SignedScalar> Triangle2<T> {
+    /// Twice the signed area, positive if the corners are counter-clockwise. Exact for
+    /// integers.
+    #[inline]
+    pub fn signed_area2(t: &Triangle2<T>) -> T {
+        cross2(&(t.b - t.a), &(t.c - t.a))
+    }
+
+    #[inline]
+    pub fn area(t: &Triangle2<T>) -> T {
+        let two = T::one() + T::one();
+        Triangle2::signed_area2(t).abs() / two
+    }
+
+    /// Weights of `a`, `b` and `c` giving `p`, `None` if the triangle is degenerate.
+    pub fn barycentric(t: &Triangle2<T>, p: &Vector2<T>) -> Option<Vector3<T>> {
+        let area = Triangle2::signed_area2(t);
+        if area == T::zero() {
+            return None;
+        }
+        let u = cross2(&(t.b - *p), &(t.c - *p)) / area;
+        let v = cross2(&(t.c - *p), &(t.a - *p)) / area;
+        Some(Vector3::new(u, v, T::one() - u - v))
+    }
+
+    /// Whether `p` is inside or on the boundary, for either winding. Degenerate triangles
+    /// contain nothing.
+    pub fn contains(t: &Triangle2<T>, p: &Vector2<T>) -> bool {
+        let zero = T::zero();
+        let d0 = cross2(&(t.b - t.a), &(*p - t.a));
+        let d1 = cross2(&(t.c - t.b), &(*p - t.b));
+        let d2 = cross2(&(t.a - t.c), &(*p - t.c));
+        let area = Triangle2::signed_area2(t);
+        if area > zero {
+            d0 >= zero && d1 >= zero && d2 >= zero
+        } else if area < zero {
+            d0 <= zero && d1 <= zero && d2 <= zero
+        } else {
+            false
+        }
+    }
+
+    #[inline]
+    pub fn from_barycentric(t: &Triangle2<T>, w: &Vector3<T>) -> Vector2<T> {
+        t.a * w.x + t.b * w.y + t.c * w.z
+    }
+}
+
+impl<T: Real> Triangle3<T> {
+    /// Twice the area along the normal, not normalized.
+    #[inline]
+    pub fn cross(t: &Triangle3<T>) -> Vector3<T> {
+        Vector3::cross(&(t.b - t.a), &(t.c - t.a))
+    }
+
+    /// Unit normal on the side from which the corners appear counter-clockwise.
+    #[inline]
+    pub fn normal(t: &Triangle3<T>) -> Vector3<T> {
+        Vector3::normalize(&Triangle3::cross(t))
+    }
+
+    #[inline]
+    pub fn area(t: &Triangle3<T>) -> T {
+        Vector3::length(&Triangle3::cross(t)) * T::from_f64(0.5)
+    }
+
+    #[inline]
+    pub fn centroid(t: &Triangle3<T>) -> Vector3<T> {
+        (t.a + t.b + t.c) * T::from_f64(1.0 / 3.0)
+    }
+
+    /// Weights of `a`, `b` and `c` giving the projection of `p` onto the triangle's plane,
+    /// `None` if the triangle is degenerate.
+    pub fn barycentric(t: &Triangle3<T>, p: &Vector3<T>) -> Option<Vector3<T>> {
+        let (e0, e1, d) = (t.b - t.a, t.c - t.a, *p - t.a);
+        let (d00, d01, d11) = (
+            Vector3::dot(&e0, &e0),
+            Vector3::dot(&e0, &e1),
+            Vector3::dot(&e1, &e1),
+        );
+        let denom = d00 * d11 - d01 * d01;
+        if denom == T::zero() {
+            return None;
+        }
+        let (d20, d21) = (Vector3::dot(&d, &e0), Vector3::dot(&d, &e1));
+        let v = (d11 * d20 - d01 * d21) / denom;
+        let w = (d00 * d21 - d01 * d20) / denom;
+        Some(Vector3::new(T::one() - v - w, v, w))
+    }
+
+    /// Whether the projection of `p` onto the triangle's plane is inside or on the
+    /// boundary.
+    #[inline]
+    pub fn contains(t: &Triangle3<T>, p: &Vector3<T>) -> bool {
+        let zero = T::zero();
+        Triangle3::barycentric(t, p).is_some_and(|w| w.x >= zero && w.y >= zero && w.z >= zero)
+    }
+
+    #[inline]
+    pub fn from_barycentric(t: &Triangle3<T>, w: &Vector3<T>) -> Vector3<T> {
+        t.a * w.x + t.b * w.y + t.c * w.z
+    }
+
+    #[inline]
+    pub fn transform(m: &Matrix4<T>, t: &Triangle3<T>) -> Triangle3<T> {
+        Triangle3::new(
+            Matrix4::transform_point(m, &t.a),
+            Matrix4::transform_point(m, &t.b),
+            Matrix4::transform_point(m, &t.c),
+        )
+    }
+
+    /// The derivatives of position along the texture coordinates `uv` of the corners, the
+    /// unnormalized tangent and bitangent. `None` if the texture mapping is degenerate.
+    pub fn tangent_frame(t: &Triangle3<T>, uv: &Triangle2<T>) -> Option<(Vector3<T>, Vector3<T>)> {
+        let (e0, e1) = (t.b - t.a, t.c - t.a);
+        let (s0, s1) = (uv.b - uv.a, uv.c - uv.a);
+        let det = cross2(&s0, &s1);
+        if det == T::zero() {
+            return None;
+        }
+        let r = det.recip();
+        let tangent = (e0 * s1.y - e1 * s0.y) * r;
+        let bitangent = (e1 * s0.x - e0 * s1.x) * r;
+        Some((tangent, bitangent))
+    }
+
+    /// Per-vertex tangents of an indexed triangle mesh following MikkTSpace: the face
+    /// tangents, projected onto each vertex's tangent plane, are averaged weighted by the
+    /// corner angle. `w` is the bitangent sign, so the bitangent is
+    /// `cross(normal, tangent) * w` as in glTF.
+    ///
+    /// Results match MikkTSpace when vertices are split wherever texture coordinates or
+    /// their winding change, as exported meshes usually are. Vertices used by no triangle
+    /// with a usable texture mapping get an arbitrary tangent.
+    ///
+    /// Panics if `tangents` or the attribute slices are shorter than the indices require.
+    pub fn generate_tangents(
+        positions: &[Vector3<T>],
+        normals: &[Vector3<T>],
+        uvs: &[Vector2<T>],
+        indices: &[[u32; 3]],
+        tangents: &mut [Vector4<T>],
+    ) {
+        let zero = T::zero();
+        for t in tangents.iter_mut() {
+            *t = Vector4::splat(zero);
+        }
+        for tri in indices {
+            let [i, j, k] = tri.map(|i| i as usize);
+            let t = Triangle3::new(positions[i], positions[j], positions[k]);
+            let uv = Triangle2::new(uvs[i], uvs[j], uvs[k]);
+            let (face_tangent, _) = match Triangle3::tangent_frame(&t, &uv) {
+                Some(frame) => frame,
+                None => continue,
+            };
+            let sign = if Triangle2::signed_area2(&uv) > zero {
+                T::one()
+            } else {
+                -T::one()
+            };
+            for (v, p, prev, next) in [(i, t.a, t.c, t.b), (j, t.b, t.a, t.c), (k, t.c, t.b, t.a)] {
+                let n = normals[v];
+                let project = |d: Vector3<T>| d - n * Vector3::dot(&n, &d);
+                let (e0, e1, tv) = (project(next - p), project(prev - p), project(face_tangent));
+                let (l0, l1, lt) = (
+                    Vector3::length(&e0),
+                    Vector3::length(&e1),
+                    Vector3::length(&tv),
+                );
+                if l0 == zero || l1 == zero || lt == zero {
+                    continue;
+                }
+                let angle = angle(Vector3::dot(&e0, &e1) / (l0 * l1));
+                let w = tv * (angle / lt);
+                tangents[v] += Vector4::new(w.x, w.y, w.z, sign * angle);
+            }
+        }
+        for (t, n) in tangents.iter_mut().zip(normals) {
+            let v = Vector3::new(t.x, t.y, t.z);
+            let l = Vector3::length(&v);
+            let v = if l > zero {
+                v * l.recip()
+            } else {
+                any_perpendicular(n)
+            };
+            let w = if t.w < zero { -T::one() } else { T::one() };
+            *t = Vector4::new(v.x, v.y, v.z, w);
+        }
+    }
+}
+
+// A unit vector perpendicular to `n`.
+fn any_perpendicular<T: Real>(n: &Vector3<T>) -> Vector3<T> {
+    let (zero, one) = (T::zero(), T::one());
+    let axis = if n.x.abs() <= n.y.abs() && n.x.abs() <= n.z.abs() {
+        Vector3::new(one, zero, zero)
+    } else if n.y.abs() <= n.z.abs() {
+        Vector3::new(zero, one, zero)
+    } else {
+        Vector3::new(zero, zero, one)
+    };
+    let p = Vector3::cross(n, &axis);
+    let l = Vector3::length(&p);
+    if l > zero {
+        p * l.recip()
+    } else {
+        axis
+    }
+}
+
+// The angle whose cosine is `c`, for `Real` types without inverse trigonometry. Halves
+// the angle until its tangent is below 0.2 and sums the arctangent series.
+fn angle<T: Real>(c: T) -> T {
+    let one = T::one();
+    let c = if c > one {
+        one
+    } else if c < -one {
+        -one
+    } else {
+        c
+    };
+    let s = (one - c * c).sqrt();
+    if c + one == T::zero() {
+        return T::from_f64(core::f64::consts::PI);
+    }
+    // tan(x / 2) = sin(x) / (1 + cos(x)), then tan(x / 2) = tan(x) / (1 + sqrt(1 + tan²(x))).
+    let mut t = s / (one + c);
+    for _ in 0..3 {
+        t = t / (one + (one + t * t).sqrt());
+    }
+    let t2 = t * t;
+    let mut sum = T::zero();
+    for k in (0..7).rev() {
+        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+        sum = sum * t2 + T::from_f64(sign / (2 * k + 1) as f64);
+    }
+    t * sum * T::from_f64(16.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn triangle2_tests() {
+        let t = Triangle2::new(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(4.0, 0.0),
+            Vector2::new(0.0, 2.0),
+        );
+        assert_eq!(Triangle2::signed_area2(&t), 8.0);
+        assert_eq!(Triangle2::area(&t), 4.0);
+        let w = Triangle2::barycentric(&t, &Vector2::new(1.0, 1.0)).unwrap();
+        assert_relative_eq!(w, Vector3::new(0.25, 0.25, 0.5));
+        assert_relative_eq!(Triangle2::from_barycentric(&t, &w), Vector2::new(1.0, 1.0));
+
+        // Both windings, with boundary points inside.
+        let flipped = Triangle2::new(t.a, t.c, t.b);
+        assert_eq!(Triangle2::signed_area2(&flipped), -8.0);
+        for t in [t, flipped] {
+            assert!(Triangle2::contains(&t, &Vector2::new(1.0, 1.0)));
+            assert!(Triangle2::contains(&t, &Vector2::new(2.0, 1.0)));
+            assert!(Triangle2::contains(&t, &Vector2::new(4.0, 0.0)));
+            assert!(!Triangle2::contains(&t, &Vector2::new(2.0, 1.5)));
+            assert!(!Triangle2::contains(&t, &Vector2::new(-0.1, 1.0)));
+        }
+
+        let line = Triangle2::new(Vector2::new(0, 0), Vector2::new(1, 1), Vector2::new(3, 3));
+        assert_eq!(Triangle2::signed_area2(&line), 0);
+        assert!(!Triangle2::contains(&line, &Vector2::new(1, 1)));
+        assert_eq!(
+            Triangle2::barycentric(
+                &Triangle2::new(
+                    Vector2::new(0.0, 0.0),
+                    Vector2::new(1.0, 1.0),
+                    Vector2::new(2.0, 2.0)
+                ),
+                &Vector2::new(1.0, 1.0)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn triangle3_tests() {
+        let t = Triangle3::new(
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(2.0, 0.0, 1.0),
+            Vector3::new(0.0, 2.0, 1.0),
+        );
+        assert_eq!(Triangle3::normal(&t), Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(Triangle3::area(&t), 2.0);
+        assert_relative_eq!(
+            Triangle3::centroid(&t),
+            Vector3::new(2.0 / 3.0, 2.0 / 3.0, 1.0)
+        );
+
+        // Points off the plane project onto it.
+        let p = Vector3::new(0.5, 0.5, 7.0);
+        let w = Triangle3::barycentric(&t, &p).unwrap();
+        assert_relative_eq!(w, Vector3::new(0.5, 0.25, 0.25));
+        assert_relative_eq!(
+            Triangle3::from_barycentric(&t, &w),
+            Vector3::new(0.5, 0.5, 1.0)
+        );
+        assert!(Triangle3::contains(&t, &p));
+        assert!(Triangle3::contains(&t, &Vector3::new(1.0, 1.0, 0.0)));
+        assert!(!Triangle3::contains(&t, &Vector3::new(1.5, 1.0, 1.0)));
+
+        let m = Matrix4::translate(&Vector3::new(0.0, 0.0, -1.0));
+        assert_eq!(Triangle3::transform(&m, &t).c, Vector3::new(0.0, 2.0, 0.0));
+
+        let collinear = Triangle3::new(t.a, t.b, (t.a + t.b) * 0.5);
+        assert_eq!(Triangle3::barycentric(&collinear, &p), None);
+        assert!(!Triangle3::contains(&collinear, &t.a));
+    }
+
+    #[test]
+    fn triangle_angle() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let c: f64 = rng.gen::<f64>() * 2.0 - 1.0;
+            assert_relative_eq!(angle(c), c.acos(), epsilon = 1e-10);
+        }
+        assert_relative_eq!(angle(-1.0), std::f64::consts::PI);
+        assert_eq!(angle(1.0), 0.0);
+        assert_relative_eq!(angle(0.5f32), std::f32::consts::FRAC_PI_3);
+    }
+
+    #[test]
+    fn triangle_tangents() {
+        // A quad in the xz plane facing +y, with u along x and v along -z.
+        let positions = [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, -1.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(5.0, 5.0, 5.0),
+        ];
+        let normals = [Vector3::new(0.0, 1.0, 0.0); 5];
+        let uvs = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(0.0, 1.0),
+            Vector2::new(0.0, 0.0),
+        ];
+        let indices = [[0, 1, 2], [0, 2, 3]];
+        let t = Triangle3::new(positions[0], positions[1], positions[2]);
+        let uv = Triangle2::new(uvs[0], uvs[1], uvs[2]);
+        let (tangent, bitangent) = Triangle3::tangent_frame(&t, &uv).unwrap();
+        assert_relative_eq!(tangent, Vector3::new(1.0, 0.0, 0.0));
+        assert_relative_eq!(bitangent, Vector3::new(0.0, 0.0, -1.0));
+
+        let mut tangents = [Vector4::splat(0.0); 5];
+        Triangle3::generate_tangents(&positions, &normals, &uvs, &indices, &mut tangents);
+        for t in &tangents[..4] {
+            assert_relative_eq!(*t, Vector4::new(1.0, 0.0, 0.0, 1.0), epsilon = 1e-12);
+        }
+        // The unused vertex gets some unit tangent perpendicular to its normal.
+        let t = Vector3::new(tangents[4].x, tangents[4].y, tangents[4].z);
+        assert_relative_eq!(Vector3::length(&t), 1.0);
+        assert_relative_eq!(Vector3::dot(&t, &normals[4]), 0.0);
+
+        // Mirroring the texture flips the tangent and the bitangent sign.
+        let mirrored: Vec<_> = uvs
+            .iter()
+            .map(|uv| Vector2::new(1.0 - uv.x, uv.y))
+            .collect();
+        Triangle3::generate_tangents(&positions, &normals, &mirrored, &indices, &mut tangents);
+        for t in &tangents[..4] {
+            assert_relative_eq!(*t, Vector4::new(-1.0, 0.0, 0.0, -1.0), epsilon = 1e-12);
+            // The glTF bitangent still points along increasing v.
+            let b = Vector3::cross(&normals[0], &Vector3::new(t.x, t.y, t.z)) * t.w;
+            assert_relative_eq!(b, Vector3::new(0.0, 0.0, -1.0), epsilon = 1e-12);
+        }
+
+        // Tilted normals get tangents projected onto their tangent plane.
+        let tilted = [Vector3::normalize(&Vector3::new(1.0, 1.0, 0.0)); 5];
+        Triangle3::generate_tangents(&positions, &tilted, &uvs, &indices, &mut tangents);
+        let h = 0.5f64.sqrt();
+        assert_relative_eq!(tangents[0], Vector4::new(h, -h, 0.0, 1.0), epsilon = 1e-12);
+    }
+}
@@ -1,26 +1,159 @@
+use crate::scalar::{Real, Scalar};
 use approx::{AbsDiffEq, RelativeEq, UlpsEq};
-use num_traits::Float;
-use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use core::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Mul,
+    MulAssign, Not, Rem, RemAssign, Sub, SubAssign,
+};
 
 macro_rules! sum {
     ($h:expr) => ($h);
     ($h:expr, $($t:expr),*) => ($h + sum!($($t),*));
 }
 
+macro_rules! generate_bvec_n {
+    ($BVecN: ident, $($field: ident),+) => {
+        #[repr(C)]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub struct $BVecN {
+            $(pub $field: bool),+
+        }
+
+        impl $BVecN {
+            #[inline]
+            pub fn new($($field: bool),+) -> $BVecN {
+                $BVecN { $($field: $field),+ }
+            }
+
+            #[inline]
+            pub fn splat(v: bool) -> $BVecN {
+                $BVecN { $($field: v),+ }
+            }
+
+            #[inline]
+            pub fn any(m: &$BVecN) -> bool {
+                $(m.$field)||+
+            }
+
+            #[inline]
+            pub fn all(m: &$BVecN) -> bool {
+                $(m.$field)&&+
+            }
+        }
+
+        impl BitAnd<$BVecN> for $BVecN {
+            type Output = $BVecN;
+
+            #[inline]
+            fn bitand(self, rhs: $BVecN) -> Self::Output {
+                $BVecN::new($(self.$field & rhs.$field),+)
+            }
+        }
+
+        impl BitOr<$BVecN> for $BVecN {
+            type Output = $BVecN;
+
+            #[inline]
+            fn bitor(self, rhs: $BVecN) -> Self::Output {
+                $BVecN::new($(self.$field | rhs.$field),+)
+            }
+        }
+
+        impl BitXor<$BVecN> for $BVecN {
+            type Output = $BVecN;
+
+            #[inline]
+            fn bitxor(self, rhs: $BVecN) -> Self::Output {
+                $BVecN::new($(self.$field ^ rhs.$field),+)
+            }
+        }
+
+        impl Not for $BVecN {
+            type Output = $BVecN;
+
+            #[inline]
+            fn not(self) -> Self::Output {
+                $BVecN::new($(!self.$field),+)
+            }
+        }
+    };
+}
+
 macro_rules! generate_vector_n {
-    ($VectorN: ident, $($field: ident),+) => {
+    ($VectorN: ident, $BVecN: ident, $($field: ident),+) => {
         #[repr(C)]
         #[derive(Copy, Clone, Debug, PartialEq)]
-        pub struct $VectorN<T: Float> {
+        pub struct $VectorN<T> {
             $(pub $field: T),+
         }
 
-        impl<T: Float> $VectorN<T> {
+        impl<T> $VectorN<T> {
             #[inline]
             pub fn new($($field: T),+) -> $VectorN<T> {
                 $VectorN { $($field: $field),+ }
             }
+        }
+
+        impl<T: Copy> $VectorN<T> {
+            #[inline]
+            pub fn splat(v: T) -> $VectorN<T> {
+                $VectorN { $($field: v),+ }
+            }
+
+            #[inline]
+            pub fn select(m: &$BVecN, a: &$VectorN<T>, b: &$VectorN<T>) -> $VectorN<T> {
+                $VectorN::new($(if m.$field { a.$field } else { b.$field }),+)
+            }
+        }
+
+        impl<T: Copy + PartialOrd> $VectorN<T> {
+            /// Component-wise minimum.
+            #[inline]
+            pub fn min(a: &$VectorN<T>, b: &$VectorN<T>) -> $VectorN<T> {
+                $VectorN::new($(if b.$field < a.$field { b.$field } else { a.$field }),+)
+            }
+
+            /// Component-wise maximum.
+            #[inline]
+            pub fn max(a: &$VectorN<T>, b: &$VectorN<T>) -> $VectorN<T> {
+                $VectorN::new($(if b.$field > a.$field { b.$field } else { a.$field }),+)
+            }
+        }
+
+        impl<T: PartialEq> $VectorN<T> {
+            #[inline]
+            pub fn cmpeq(a: &$VectorN<T>, b: &$VectorN<T>) -> $BVecN {
+                $BVecN::new($(a.$field == b.$field),+)
+            }
+
+            #[inline]
+            pub fn cmpne(a: &$VectorN<T>, b: &$VectorN<T>) -> $BVecN {
+                $BVecN::new($(a.$field != b.$field),+)
+            }
+        }
+
+        impl<T: PartialOrd> $VectorN<T> {
+            #[inline]
+            pub fn cmplt(a: &$VectorN<T>, b: &$VectorN<T>) -> $BVecN {
+                $BVecN::new($(a.$field < b.$field),+)
+            }
+
+            #[inline]
+            pub fn cmple(a: &$VectorN<T>, b: &$VectorN<T>) -> $BVecN {
+                $BVecN::new($(a.$field <= b.$field),+)
+            }
+
+            #[inline]
+            pub fn cmpgt(a: &$VectorN<T>, b: &$VectorN<T>) -> $BVecN {
+                $BVecN::new($(a.$field > b.$field),+)
+            }
+
+            #[inline]
+            pub fn cmpge(a: &$VectorN<T>, b: &$VectorN<T>) -> $BVecN {
+                $BVecN::new($(a.$field >= b.$field),+)
+            }
+        }
 
+        impl<T: Scalar> $VectorN<T> {
             #[inline]
             fn add_vector_and_vector(a: &$VectorN<T>, b: &$VectorN<T>) -> $VectorN<T> {
                 $VectorN::new($(a.$field + b.$field),+)
@@ -40,7 +173,16 @@ macro_rules! generate_vector_n {
             pub fn dot(a: &$VectorN<T>, b: &$VectorN<T>) -> T {
                 sum!($(a.$field * b.$field),+)
             }
+        }
+
+        impl<T: Scalar + Rem<Output = T>> $VectorN<T> {
+            #[inline]
+            fn rem_vector_and_scalar(a: &$VectorN<T>, b: &T) -> $VectorN<T> {
+                $VectorN::new($(a.$field % *b),+)
+            }
+        }
 
+        impl<T: Real> $VectorN<T> {
             #[inline]
             pub fn length(v: &$VectorN<T>) -> T {
                 $VectorN::dot(v, v).sqrt()
@@ -52,7 +194,7 @@ macro_rules! generate_vector_n {
             }
         }
 
-        impl<T: Float> Add<$VectorN<T>> for $VectorN<T> {
+        impl<T: Scalar> Add<$VectorN<T>> for $VectorN<T> {
             type Output = $VectorN<T>;
 
             #[inline]
@@ -61,7 +203,7 @@ macro_rules! generate_vector_n {
             }
         }
 
-        impl<T: Float> Sub<$VectorN<T>> for $VectorN<T> {
+        impl<T: Scalar> Sub<$VectorN<T>> for $VectorN<T> {
             type Output = $VectorN<T>;
 
             #[inline]
@@ -70,7 +212,7 @@ macro_rules! generate_vector_n {
             }
         }
 
-        impl<T: Float> Mul<T> for $VectorN<T> {
+        impl<T: Scalar> Mul<T> for $VectorN<T> {
             type Output = $VectorN<T>;
 
             #[inline]
@@ -79,30 +221,102 @@ macro_rules! generate_vector_n {
             }
         }
 
-        impl<T: Float> AddAssign<$VectorN<T>> for $VectorN<T> {
+        impl<T: Scalar> AddAssign<$VectorN<T>> for $VectorN<T> {
             #[inline]
             fn add_assign(&mut self, rhs: $VectorN<T>) {
                 *self = $VectorN::add_vector_and_vector(self, &rhs)
             }
         }
 
-        impl<T: Float> SubAssign<$VectorN<T>> for $VectorN<T> {
+        impl<T: Scalar> SubAssign<$VectorN<T>> for $VectorN<T> {
             #[inline]
             fn sub_assign(&mut self, rhs: $VectorN<T>) {
                 *self = $VectorN::sub_vector_and_vector(self, &rhs)
             }
         }
 
-        impl<T: Float> MulAssign<T> for $VectorN<T> {
+        impl<T: Scalar> MulAssign<T> for $VectorN<T> {
             #[inline]
             fn mul_assign(&mut self, rhs: T) {
                 *self = $VectorN::mul_vector_and_scalar(self, &rhs)
             }
         }
 
+        impl<T: Scalar + Rem<Output = T>> Rem<T> for $VectorN<T> {
+            type Output = $VectorN<T>;
+
+            #[inline]
+            fn rem(self, rhs: T) -> Self::Output {
+                $VectorN::rem_vector_and_scalar(&self, &rhs)
+            }
+        }
+
+        impl<T: Scalar + Rem<Output = T>> RemAssign<T> for $VectorN<T> {
+            #[inline]
+            fn rem_assign(&mut self, rhs: T) {
+                *self = $VectorN::rem_vector_and_scalar(self, &rhs)
+            }
+        }
+
+        impl<T: BitAnd<Output = T>> BitAnd<$VectorN<T>> for $VectorN<T> {
+            type Output = $VectorN<T>;
+
+            #[inline]
+            fn bitand(self, rhs: $VectorN<T>) -> Self::Output {
+                $VectorN::new($(self.$field & rhs.$field),+)
+            }
+        }
+
+        impl<T: BitOr<Output = T>> BitOr<$VectorN<T>> for $VectorN<T> {
+            type Output = $VectorN<T>;
+
+            #[inline]
+            fn bitor(self, rhs: $VectorN<T>) -> Self::Output {
+                $VectorN::new($(self.$field | rhs.$field),+)
+            }
+        }
+
+        impl<T: BitXor<Output = T>> BitXor<$VectorN<T>> for $VectorN<T> {
+            type Output = $VectorN<T>;
+
+            #[inline]
+            fn bitxor(self, rhs: $VectorN<T>) -> Self::Output {
+                $VectorN::new($(self.$field ^ rhs.$field),+)
+            }
+        }
+
+        impl<T: Not<Output = T>> Not for $VectorN<T> {
+            type Output = $VectorN<T>;
+
+            #[inline]
+            fn not(self) -> Self::Output {
+                $VectorN::new($(!self.$field),+)
+            }
+        }
+
+        impl<T: BitAndAssign> BitAndAssign<$VectorN<T>> for $VectorN<T> {
+            #[inline]
+            fn bitand_assign(&mut self, rhs: $VectorN<T>) {
+                $(self.$field &= rhs.$field;)+
+            }
+        }
+
+        impl<T: BitOrAssign> BitOrAssign<$VectorN<T>> for $VectorN<T> {
+            #[inline]
+            fn bitor_assign(&mut self, rhs: $VectorN<T>) {
+                $(self.$field |= rhs.$field;)+
+            }
+        }
+
+        impl<T: BitXorAssign> BitXorAssign<$VectorN<T>> for $VectorN<T> {
+            #[inline]
+            fn bitxor_assign(&mut self, rhs: $VectorN<T>) {
+                $(self.$field ^= rhs.$field;)+
+            }
+        }
+
         impl<T: AbsDiffEq> AbsDiffEq for $VectorN<T> where
             T::Epsilon: Copy,
-            T: Float,
         {
             type Epsilon = T::Epsilon;
 
@@ -119,7 +333,6 @@ macro_rules! generate_vector_n {
 
         impl<T: RelativeEq> RelativeEq for $VectorN<T> where
             T::Epsilon: Copy,
-            T: Float,
         {
             #[inline]
             fn default_max_relative() -> T::Epsilon {
@@ -134,7 +347,6 @@ macro_rules! generate_vector_n {
 
         impl<T: UlpsEq> UlpsEq for $VectorN<T> where
             T::Epsilon: Copy,
-            T: Float,
         {
             #[inline]
             fn default_max_ulps() -> u32 {
@@ -149,11 +361,15 @@ macro_rules! generate_vector_n {
     };
 }
 
-generate_vector_n!(Vector2, x, y);
-generate_vector_n!(Vector3, x, y, z);
-generate_vector_n!(Vector4, x, y, z, w);
+generate_bvec_n!(BVec2, x, y);
+generate_bvec_n!(BVec3, x, y, z);
+generate_bvec_n!(BVec4, x, y, z, w);
+
+generate_vector_n!(Vector2, BVec2, x, y);
+generate_vector_n!(Vector3, BVec3, x, y, z);
+generate_vector_n!(Vector4, BVec4, x, y, z, w);
 
-impl<T: Float> Vector3<T> {
+impl<T: Scalar> Vector3<T> {
     #[inline]
     pub fn cross(a: &Vector3<T>, b: &Vector3<T>) -> Vector3<T> {
         Vector3::new(
@@ -211,6 +427,14 @@ mod tests {
         assert_eq!(v, Vector2::new(2.0, 4.0));
     }
 
+    #[test]
+    fn vector2_min_max() {
+        let a = Vector2::new(1.0, 4.0);
+        let b = Vector2::new(3.0, 2.0);
+        assert_eq!(Vector2::min(&a, &b), Vector2::new(1.0, 2.0));
+        assert_eq!(Vector2::max(&a, &b), Vector2::new(3.0, 4.0));
+    }
+
     #[test]
     fn vector2_dot() {
         let a = Vector2::new(1.0, 2.0);
@@ -360,4 +584,88 @@ mod tests {
         let v = Vector4::new(2.0, 0.0, 0.0, 0.0);
         assert_eq!(Vector4::normalize(&v), Vector4::new(1.0, 0.0, 0.0, 0.0))
     }
+
+    #[test]
+    fn vector2_i32_add() {
+        let a = Vector2::new(1, -2);
+        let b = Vector2::new(3, 4);
+        assert_eq!(a + b, Vector2::new(4, 2));
+    }
+
+    #[test]
+    fn vector2_i32_rem() {
+        let v = Vector2::new(17, 35);
+        assert_eq!(v % 16, Vector2::new(1, 3));
+    }
+
+    #[test]
+    fn vector3_u32_dot() {
+        let a = Vector3::new(1u32, 2, 3);
+        let b = Vector3::new(4u32, 5, 6);
+        assert_eq!(Vector3::dot(&a, &b), 32);
+    }
+
+    #[test]
+    fn vector3_i64_cross() {
+        let a = Vector3::new(1i64, 2, 3);
+        let b = Vector3::new(4i64, 5, 6);
+        assert_eq!(Vector3::cross(&a, &b), Vector3::new(-3, 6, -3));
+    }
+
+    #[test]
+    fn vector4_u8_bitwise() {
+        let a = Vector4::new(0b1100u8, 0b1010, 0xff, 0);
+        let b = Vector4::new(0b1010u8, 0b0110, 0x0f, 0);
+        assert_eq!(a & b, Vector4::new(0b1000, 0b0010, 0x0f, 0));
+        assert_eq!(a | b, Vector4::new(0b1110, 0b1110, 0xff, 0));
+        assert_eq!(a ^ b, Vector4::new(0b0110, 0b1100, 0xf0, 0));
+        assert_eq!(!a, Vector4::new(0b1111_0011, 0b1111_0101, 0, 0xff));
+    }
+
+    #[test]
+    fn vector4_u8_bitwise_assign() {
+        let mut v = Vector4::new(0b1100u8, 0b1010, 0xff, 0);
+        v &= Vector4::new(0b0100, 0b0010, 0x0f, 0xff);
+        v |= Vector4::new(0b0001, 0, 0, 0);
+        v ^= Vector4::new(0, 0, 0x0f, 0x01);
+        assert_eq!(v, Vector4::new(0b0101, 0b0010, 0, 0x01));
+    }
+
+    #[test]
+    fn vector3_cmp() {
+        let a = Vector3::new(1, 5, 3);
+        let b = Vector3::new(2, 5, 1);
+        assert_eq!(Vector3::cmplt(&a, &b), BVec3::new(true, false, false));
+        assert_eq!(Vector3::cmple(&a, &b), BVec3::new(true, true, false));
+        assert_eq!(Vector3::cmpgt(&a, &b), BVec3::new(false, false, true));
+        assert_eq!(Vector3::cmpge(&a, &b), BVec3::new(false, true, true));
+        assert_eq!(Vector3::cmpeq(&a, &b), BVec3::new(false, true, false));
+        assert_eq!(Vector3::cmpne(&a, &b), BVec3::new(true, false, true));
+    }
+
+    #[test]
+    fn bvec2_any_all() {
+        assert!(BVec2::any(&BVec2::new(false, true)));
+        assert!(!BVec2::any(&BVec2::new(false, false)));
+        assert!(BVec2::all(&BVec2::new(true, true)));
+        assert!(!BVec2::all(&BVec2::new(true, false)));
+    }
+
+    #[test]
+    fn bvec4_ops() {
+        let a = BVec4::new(true, true, false, false);
+        let b = BVec4::new(true, false, true, false);
+        assert_eq!(a & b, BVec4::new(true, false, false, false));
+        assert_eq!(a | b, BVec4::new(true, true, true, false));
+        assert_eq!(a ^ b, BVec4::new(false, true, true, false));
+        assert_eq!(!a, BVec4::new(false, false, true, true));
+    }
+
+    #[test]
+    fn vector4_select() {
+        let a = Vector4::new(1.0, 2.0, 3.0, 4.0);
+        let b = Vector4::new(4.0, 3.0, 2.0, 1.0);
+        let m = Vector4::cmplt(&a, &b);
+        assert_eq!(Vector4::select(&m, &a, &b), Vector4::new(1.0, 2.0, 2.0, 1.0));
+    }
 }
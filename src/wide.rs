@@ -0,0 +1,338 @@
+// Structure-of-arrays vectors: each field holds one component for `LANES` vectors, so that
+// component-wise loops map directly onto SIMD registers.
+
+use crate::matrix::Matrix4;
+use crate::scalar::{Real, Scalar};
+use crate::vector::Vector3;
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+use core::array;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+
+macro_rules! generate_vector3_wide {
+    ($VectorN: ident, $lanes: expr) => {
+        #[repr(C)]
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub struct $VectorN<T> {
+            pub x: [T; $lanes],
+            pub y: [T; $lanes],
+            pub z: [T; $lanes],
+        }
+
+        impl<T> $VectorN<T> {
+            pub const LANES: usize = $lanes;
+
+            #[inline]
+            pub fn new(x: [T; $lanes], y: [T; $lanes], z: [T; $lanes]) -> $VectorN<T> {
+                $VectorN { x, y, z }
+            }
+        }
+
+        impl<T: Copy> $VectorN<T> {
+            #[inline]
+            pub fn splat(v: &Vector3<T>) -> $VectorN<T> {
+                $VectorN::new([v.x; $lanes], [v.y; $lanes], [v.z; $lanes])
+            }
+
+            #[inline]
+            pub fn lane(v: &$VectorN<T>, i: usize) -> Vector3<T> {
+                Vector3::new(v.x[i], v.y[i], v.z[i])
+            }
+
+            /// Gathers the first `LANES` vectors of `s`. Panics if `s` is shorter than that.
+            #[inline]
+            pub fn from_slice(s: &[Vector3<T>]) -> $VectorN<T> {
+                let s = &s[..$lanes];
+                $VectorN::new(
+                    array::from_fn(|i| s[i].x),
+                    array::from_fn(|i| s[i].y),
+                    array::from_fn(|i| s[i].z),
+                )
+            }
+
+            /// Scatters the lanes into the first `LANES` elements of `s`. Panics if `s` is
+            /// shorter than that.
+            #[inline]
+            pub fn write_to_slice(v: &$VectorN<T>, s: &mut [Vector3<T>]) {
+                for (i, p) in s[..$lanes].iter_mut().enumerate() {
+                    *p = $VectorN::lane(v, i);
+                }
+            }
+        }
+
+        impl<T: Scalar> $VectorN<T> {
+            #[inline]
+            fn add_vector_and_vector(a: &$VectorN<T>, b: &$VectorN<T>) -> $VectorN<T> {
+                $VectorN::new(
+                    array::from_fn(|i| a.x[i] + b.x[i]),
+                    array::from_fn(|i| a.y[i] + b.y[i]),
+                    array::from_fn(|i| a.z[i] + b.z[i]),
+                )
+            }
+
+            #[inline]
+            fn sub_vector_and_vector(a: &$VectorN<T>, b: &$VectorN<T>) -> $VectorN<T> {
+                $VectorN::new(
+                    array::from_fn(|i| a.x[i] - b.x[i]),
+                    array::from_fn(|i| a.y[i] - b.y[i]),
+                    array::from_fn(|i| a.z[i] - b.z[i]),
+                )
+            }
+
+            #[inline]
+            fn mul_vector_and_scalar(a: &$VectorN<T>, b: &T) -> $VectorN<T> {
+                $VectorN::new(
+                    array::from_fn(|i| a.x[i] * *b),
+                    array::from_fn(|i| a.y[i] * *b),
+                    array::from_fn(|i| a.z[i] * *b),
+                )
+            }
+
+            #[inline]
+            fn mul_vector_and_lanes(a: &$VectorN<T>, b: &[T; $lanes]) -> $VectorN<T> {
+                $VectorN::new(
+                    array::from_fn(|i| a.x[i] * b[i]),
+                    array::from_fn(|i| a.y[i] * b[i]),
+                    array::from_fn(|i| a.z[i] * b[i]),
+                )
+            }
+
+            #[inline]
+            pub fn dot(a: &$VectorN<T>, b: &$VectorN<T>) -> [T; $lanes] {
+                array::from_fn(|i| a.x[i] * b.x[i] + (a.y[i] * b.y[i] + a.z[i] * b.z[i]))
+            }
+
+            #[inline]
+            pub fn cross(a: &$VectorN<T>, b: &$VectorN<T>) -> $VectorN<T> {
+                $VectorN::new(
+                    array::from_fn(|i| a.y[i] * b.z[i] - a.z[i] * b.y[i]),
+                    array::from_fn(|i| a.z[i] * b.x[i] - a.x[i] * b.z[i]),
+                    array::from_fn(|i| a.x[i] * b.y[i] - a.y[i] * b.x[i]),
+                )
+            }
+
+            fn transform_points(m: &Matrix4<T>, p: &$VectorN<T>) -> $VectorN<T> {
+                $VectorN::new(
+                    array::from_fn(|i| {
+                        m.x.x * p.x[i] + (m.y.x * p.y[i] + (m.z.x * p.z[i] + m.w.x))
+                    }),
+                    array::from_fn(|i| {
+                        m.x.y * p.x[i] + (m.y.y * p.y[i] + (m.z.y * p.z[i] + m.w.y))
+                    }),
+                    array::from_fn(|i| {
+                        m.x.z * p.x[i] + (m.y.z * p.y[i] + (m.z.z * p.z[i] + m.w.z))
+                    }),
+                )
+            }
+        }
+
+        impl<T: Real> $VectorN<T> {
+            #[inline]
+            pub fn length(v: &$VectorN<T>) -> [T; $lanes] {
+                $VectorN::dot(v, v).map(T::sqrt)
+            }
+
+            #[inline]
+            pub fn normalize(v: &$VectorN<T>) -> $VectorN<T> {
+                $VectorN::mul_vector_and_lanes(v, &$VectorN::length(v).map(T::recip))
+            }
+        }
+
+        impl<T: Copy> From<[Vector3<T>; $lanes]> for $VectorN<T> {
+            #[inline]
+            fn from(a: [Vector3<T>; $lanes]) -> $VectorN<T> {
+                $VectorN::from_slice(&a)
+            }
+        }
+
+        impl<T: Copy> From<$VectorN<T>> for [Vector3<T>; $lanes] {
+            #[inline]
+            fn from(v: $VectorN<T>) -> [Vector3<T>; $lanes] {
+                array::from_fn(|i| $VectorN::lane(&v, i))
+            }
+        }
+
+        impl<T: Scalar> Add<$VectorN<T>> for $VectorN<T> {
+            type Output = $VectorN<T>;
+
+            #[inline]
+            fn add(self, rhs: $VectorN<T>) -> Self::Output {
+                $VectorN::add_vector_and_vector(&self, &rhs)
+            }
+        }
+
+        impl<T: Scalar> Sub<$VectorN<T>> for $VectorN<T> {
+            type Output = $VectorN<T>;
+
+            #[inline]
+            fn sub(self, rhs: $VectorN<T>) -> Self::Output {
+                $VectorN::sub_vector_and_vector(&self, &rhs)
+            }
+        }
+
+        impl<T: Scalar> Mul<T> for $VectorN<T> {
+            type Output = $VectorN<T>;
+
+            #[inline]
+            fn mul(self, rhs: T) -> Self::Output {
+                $VectorN::mul_vector_and_scalar(&self, &rhs)
+            }
+        }
+
+        impl<T: Scalar> AddAssign<$VectorN<T>> for $VectorN<T> {
+            #[inline]
+            fn add_assign(&mut self, rhs: $VectorN<T>) {
+                *self = $VectorN::add_vector_and_vector(self, &rhs)
+            }
+        }
+
+        impl<T: Scalar> SubAssign<$VectorN<T>> for $VectorN<T> {
+            #[inline]
+            fn sub_assign(&mut self, rhs: $VectorN<T>) {
+                *self = $VectorN::sub_vector_and_vector(self, &rhs)
+            }
+        }
+
+        impl<T: Scalar> MulAssign<T> for $VectorN<T> {
+            #[inline]
+            fn mul_assign(&mut self, rhs: T) {
+                *self = $VectorN::mul_vector_and_scalar(self, &rhs)
+            }
+        }
+
+        /// Transforms every lane as a point, like `Matrix4::transform_point`.
+        impl<T: Scalar> Mul<$VectorN<T>> for Matrix4<T> {
+            type Output = $VectorN<T>;
+
+            #[inline]
+            fn mul(self, rhs: $VectorN<T>) -> Self::Output {
+                $VectorN::transform_points(&self, &rhs)
+            }
+        }
+
+        impl<T: AbsDiffEq> AbsDiffEq for $VectorN<T>
+        where
+            T::Epsilon: Copy,
+        {
+            type Epsilon = T::Epsilon;
+
+            #[inline]
+            fn default_epsilon() -> T::Epsilon {
+                T::default_epsilon()
+            }
+
+            #[inline]
+            fn abs_diff_eq(&self, other: &Self, epsilon: T::Epsilon) -> bool {
+                <[T]>::abs_diff_eq(&self.x[..], &other.x[..], epsilon)
+                    && <[T]>::abs_diff_eq(&self.y[..], &other.y[..], epsilon)
+                    && <[T]>::abs_diff_eq(&self.z[..], &other.z[..], epsilon)
+            }
+        }
+
+        impl<T: RelativeEq> RelativeEq for $VectorN<T>
+        where
+            T::Epsilon: Copy,
+        {
+            #[inline]
+            fn default_max_relative() -> T::Epsilon {
+                T::default_max_relative()
+            }
+
+            #[inline]
+            fn relative_eq(
+                &self,
+                other: &Self,
+                epsilon: T::Epsilon,
+                max_relative: T::Epsilon,
+            ) -> bool {
+                <[T]>::relative_eq(&self.x[..], &other.x[..], epsilon, max_relative)
+                    && <[T]>::relative_eq(&self.y[..], &other.y[..], epsilon, max_relative)
+                    && <[T]>::relative_eq(&self.z[..], &other.z[..], epsilon, max_relative)
+            }
+        }
+
+        impl<T: UlpsEq> UlpsEq for $VectorN<T>
+        where
+            T::Epsilon: Copy,
+        {
+            #[inline]
+            fn default_max_ulps() -> u32 {
+                T::default_max_ulps()
+            }
+
+            #[inline]
+            fn ulps_eq(&self, other: &Self, epsilon: T::Epsilon, max_ulps: u32) -> bool {
+                <[T]>::ulps_eq(&self.x[..], &other.x[..], epsilon, max_ulps)
+                    && <[T]>::ulps_eq(&self.y[..], &other.y[..], epsilon, max_ulps)
+                    && <[T]>::ulps_eq(&self.z[..], &other.z[..], epsilon, max_ulps)
+            }
+        }
+    };
+}
+
+generate_vector3_wide!(Vector3x4, 4);
+generate_vector3_wide!(Vector3x8, 8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points() -> [Vector3<f32>; 8] {
+        array::from_fn(|i| {
+            let i = i as f32;
+            Vector3::new(i + 1.0, 2.0 * i - 3.0, 0.5 - i)
+        })
+    }
+
+    #[test]
+    fn vector3x8_slice_roundtrip() {
+        let p = points();
+        let w = Vector3x8::from_slice(&p);
+        assert_eq!(Vector3x8::lane(&w, 3), p[3]);
+        let mut out = [Vector3::splat(0.0); 8];
+        Vector3x8::write_to_slice(&w, &mut out);
+        assert_eq!(out, p);
+        assert_eq!(<[Vector3<f32>; 8]>::from(Vector3x8::from(p)), p);
+    }
+
+    #[test]
+    fn vector3x4_add_sub_mul() {
+        let p = points();
+        let a = Vector3x4::from_slice(&p[..4]);
+        let b = Vector3x4::from_slice(&p[4..]);
+        let r: [Vector3<f32>; 4] = ((a + b) * 2.0 - a).into();
+        for i in 0..4 {
+            assert_eq!(r[i], (p[i] + p[i + 4]) * 2.0 - p[i]);
+        }
+    }
+
+    #[test]
+    fn vector3x8_dot_cross_normalize() {
+        let p = points();
+        let q: [Vector3<f32>; 8] = array::from_fn(|i| p[7 - i]);
+        let a = Vector3x8::from(p);
+        let b = Vector3x8::from(q);
+        let dot = Vector3x8::dot(&a, &b);
+        let cross = Vector3x8::cross(&a, &b);
+        let normalized = Vector3x8::normalize(&a);
+        for i in 0..8 {
+            assert_eq!(dot[i], Vector3::dot(&p[i], &q[i]));
+            assert_eq!(Vector3x8::lane(&cross, i), Vector3::cross(&p[i], &q[i]));
+            assert_relative_eq!(Vector3x8::lane(&normalized, i), Vector3::normalize(&p[i]));
+        }
+    }
+
+    #[test]
+    fn matrix4_mul_vector3x8() {
+        let m = Matrix4::translate(&Vector3::new(1.0, -2.0, 3.0))
+            * Matrix4::rotate(0.5, &Vector3::normalize(&Vector3::new(1.0, 1.0, 0.0)));
+        let p = points();
+        let r = m * Vector3x8::from(p);
+        for (i, p) in p.iter().enumerate() {
+            assert_relative_eq!(
+                Vector3x8::lane(&r, i),
+                Matrix4::transform_point(&m, p),
+                epsilon = 1e-5
+            );
+        }
+    }
+}